@@ -0,0 +1,3 @@
+A Y
+B X
+C Z
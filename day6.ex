@@ -0,0 +1 @@
+bvwbjplbgvbhsrlpgdmjqwftvncz
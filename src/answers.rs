@@ -0,0 +1,134 @@
+use crate::solver::{Answer, Part};
+use std::collections::HashMap;
+
+pub struct Answers {
+    expected: HashMap<(u32, u32), Answer>
+}
+
+fn parse_error(line_no: usize, msg: &str) -> Box<dyn std::error::Error> {
+    Box::from(format!("answers line {}: {}", line_no + 1, msg))
+}
+
+fn parse_value(value: &str, line_no: usize) -> Result<Answer, Box<dyn std::error::Error>> {
+    if let Some(text) = value.strip_prefix('"') {
+        return match text.strip_suffix('"') {
+            None => Err(parse_error(line_no, "unterminated string")),
+            Some(text) => Ok(Answer::Text(text.to_string()))
+        }
+    }
+
+    match value.parse::<i64>() {
+        Ok(v) => Ok(Answer::Int(v)),
+        Err(_) => Err(parse_error(line_no, &format!("invalid value {}", value)))
+    }
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Answers, Box<dyn std::error::Error>> {
+        let mut expected = HashMap::new();
+        let mut day = None;
+
+        let mut lines = input.lines().enumerate();
+        while let Some((line_no, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(section) = line.strip_prefix("[day").and_then(|s| s.strip_suffix(']')) {
+                day = Some(section.parse::<u32>().map_err(|_| parse_error(line_no, "invalid day section"))?);
+                continue;
+            }
+
+            let day = match day {
+                None => return Err(parse_error(line_no, "expected a [dayN] section")),
+                Some(d) => d
+            };
+
+            let (key, value) = match line.split_once('=') {
+                None => return Err(parse_error(line_no, "expected partN = <answer>")),
+                Some((k, v)) => (k.trim(), v.trim())
+            };
+
+            let part = match key {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(parse_error(line_no, &format!("unknown key {}", key)))
+            };
+
+            let answer = if value == "\"\"\"" {
+                let mut rows = vec![];
+                loop {
+                    match lines.next() {
+                        None => return Err(parse_error(line_no, "unterminated multi-line string")),
+                        Some((_, row)) if row.trim() == "\"\"\"" => break,
+                        Some((_, row)) => rows.push(row.to_string())
+                    }
+                }
+                Answer::Grid(rows)
+            }
+            else {
+                parse_value(value, line_no)?
+            };
+
+            expected.insert((day, part), answer);
+        }
+
+        Ok(Answers { expected })
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&Answer> {
+        self.expected.get(&(day, part.number()))
+    }
+}
+
+pub fn matches(expected: &Answer, actual: &Answer) -> bool {
+    match (expected, actual) {
+        (Answer::Grid(a), Answer::Grid(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.trim_end() == y.trim_end())
+        },
+        _ => expected == actual
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input =
+r#"# comment
+[day1]
+part1 = 69626
+part2 = "abc"
+
+[day10]
+part2 = """
+#..#
+.##.
+"""
+"#;
+        let answers = Answers::parse(input).unwrap();
+        assert_eq!(answers.get(1, Part::One), Some(&Answer::Int(69626)));
+        assert_eq!(answers.get(1, Part::Two), Some(&Answer::Text("abc".to_owned())));
+        assert_eq!(answers.get(10, Part::One), None);
+        assert_eq!(answers.get(10, Part::Two), Some(&Answer::Grid(vec!["#..#".to_owned(), ".##.".to_owned()])));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Answers::parse("part1 = 1").is_err());
+        assert!(Answers::parse("[day1]\npart3 = 1").is_err());
+        assert!(Answers::parse("[day1]\npart1 = \"abc").is_err());
+        assert!(Answers::parse("[day1]\npart1 = \"\"\"\n#.").is_err());
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches(&Answer::Int(5), &Answer::Int(5)));
+        assert!(!matches(&Answer::Int(5), &Answer::Int(6)));
+        assert!(matches(&Answer::Grid(vec!["#.".to_owned()]), &Answer::Grid(vec!["#.  ".to_owned()])));
+        assert!(!matches(&Answer::Grid(vec!["#.".to_owned()]), &Answer::Grid(vec!["#.".to_owned(), "".to_owned()])));
+    }
+}
@@ -0,0 +1,211 @@
+use crate::runner::millis;
+use crate::solver::{Day, Part, PARTS};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration
+}
+
+// Divides in f64 so a sample count above u32::MAX isn't truncated.
+fn mean(total: Duration, n: usize) -> Duration {
+    Duration::from_secs_f64(total.as_secs_f64() / n as f64)
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let n = sorted.len();
+        if n == 0 {
+            return Stats { min: Duration::ZERO, median: Duration::ZERO, mean: Duration::ZERO, p95: Duration::ZERO }
+        }
+
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        }
+        else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        };
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean: mean(sorted.iter().sum(), n),
+            p95: sorted[(n * 95).div_ceil(100) - 1]
+        }
+    }
+}
+
+pub struct Bench {
+    pub day: u32,
+    pub part: Part,
+    pub bytes: usize,
+    pub parse: Stats,
+    pub solve: Stats
+}
+
+pub fn bench_day(day: &Day, contents: &str, iterations: usize) -> Result<std::vec::Vec<Bench>, Box<dyn std::error::Error>> {
+    let mut benches = vec![];
+
+    for part in PARTS {
+        let mut parse_times = vec![];
+        let mut solve_times = vec![];
+
+        for _ in 0..iterations {
+            let start = Instant::now();
+            let puzzle = day.parse(contents)?;
+            parse_times.push(start.elapsed());
+
+            let start = Instant::now();
+            puzzle.solve(part)?;
+            solve_times.push(start.elapsed());
+        }
+
+        benches.push(Bench {
+            day: day.day,
+            part,
+            bytes: contents.len(),
+            parse: Stats::from_samples(&parse_times),
+            solve: Stats::from_samples(&solve_times)
+        });
+    }
+
+    Ok(benches)
+}
+
+pub struct Baseline {
+    medians: HashMap<(u32, u32, String), Duration>
+}
+
+impl Baseline {
+    pub fn parse(input: &str) -> Result<Baseline, Box<dyn std::error::Error>> {
+        let mut medians = HashMap::new();
+
+        for (line_no, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: std::vec::Vec<&str> = line.split_whitespace().collect();
+            let entry = match fields[..] {
+                [day, part, phase, nanos] => day.parse::<u32>().ok()
+                    .zip(part.parse::<u32>().ok())
+                    .zip(nanos.parse::<u64>().ok())
+                    .map(|((day, part), nanos)| ((day, part, phase.to_string()), Duration::from_nanos(nanos))),
+                _ => None
+            };
+
+            match entry {
+                None => return Err(Box::from(format!("baseline line {}: expected <day> <part> <phase> <nanos>", line_no + 1))),
+                Some((key, median)) => medians.insert(key, median)
+            };
+        }
+
+        Ok(Baseline { medians })
+    }
+
+    pub fn render(benches: &[Bench]) -> String {
+        let mut out = String::from("# day part phase median_nanos\n");
+        for bench in benches {
+            for (phase, stats) in [("parse", &bench.parse), ("solve", &bench.solve)] {
+                out += &format!("{} {} {} {}\n", bench.day, bench.part.number(), phase, stats.median.as_nanos());
+            }
+        }
+        out
+    }
+
+    pub fn get(&self, day: u32, part: Part, phase: &str) -> Option<Duration> {
+        self.medians.get(&(day, part.number(), phase.to_string())).copied()
+    }
+}
+
+fn throughput(bytes: usize, d: Duration) -> String {
+    if d.is_zero() {
+        return "-".to_string()
+    }
+
+    let rate = bytes as f64 / d.as_secs_f64();
+    if rate >= 1e6 {
+        format!("{:.1} MB/s", rate / 1e6)
+    }
+    else if rate >= 1e3 {
+        format!("{:.1} kB/s", rate / 1e3)
+    }
+    else {
+        format!("{:.1} B/s", rate)
+    }
+}
+
+fn change(current: Duration, baseline: Option<Duration>) -> String {
+    match baseline {
+        Some(b) if !b.is_zero() => format!("{:+.1}%", (current.as_secs_f64() / b.as_secs_f64() - 1.0) * 100.0),
+        _ => "-".to_string()
+    }
+}
+
+pub fn print_bench(benches: &[Bench], iterations: usize, baseline: Option<&Baseline>) {
+    if let Some(first) = benches.first() {
+        println!("Day {}: {} bytes, {} iterations", first.day, first.bytes, iterations);
+    }
+
+    println!("{:>5} {:<6} {:>10} {:>10} {:>10} {:>10} {:>12} {:>9}",
+        "Part", "Phase", "Min (ms)", "Med (ms)", "Mean (ms)", "P95 (ms)", "Throughput", "Baseline");
+
+    for bench in benches {
+        for (phase, stats) in [("parse", &bench.parse), ("solve", &bench.solve)] {
+            println!("{:>5} {:<6} {:>10.3} {:>10.3} {:>10.3} {:>10.3} {:>12} {:>9}",
+                bench.part.number(),
+                phase,
+                millis(stats.min),
+                millis(stats.median),
+                millis(stats.mean),
+                millis(stats.p95),
+                throughput(bench.bytes, stats.median),
+                change(stats.median, baseline.and_then(|b| b.get(bench.day, bench.part, phase))));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples: std::vec::Vec<Duration> = [5, 1, 4, 2, 3].iter().map(|&ms| Duration::from_millis(ms)).collect();
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.mean, Duration::from_millis(3));
+        assert_eq!(stats.p95, Duration::from_millis(5));
+
+        let stats = Stats::from_samples(&samples[1..]);
+        assert_eq!(stats.median, Duration::from_micros(2500));
+
+        assert_eq!(mean(Duration::from_secs(1 << 33), 1 << 33), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_baseline() {
+        let stats = Stats::from_samples(&[Duration::from_nanos(1500)]);
+        let benches = vec![Bench { day: 11, part: Part::Two, bytes: 10, parse: stats, solve: stats }];
+
+        let baseline = Baseline::parse(&Baseline::render(&benches)).unwrap();
+        assert_eq!(baseline.get(11, Part::Two, "solve"), Some(Duration::from_nanos(1500)));
+        assert_eq!(baseline.get(11, Part::One, "solve"), None);
+        assert!(Baseline::parse("11 2 solve").is_err());
+    }
+
+    #[test]
+    fn test_change() {
+        assert_eq!(change(Duration::from_millis(90), Some(Duration::from_millis(100))), "-10.0%");
+        assert_eq!(change(Duration::from_millis(90), None), "-");
+    }
+}
@@ -1,17 +1,200 @@
-use std::fs;
+use std::io::Read;
 
-pub fn read_file2(file_name: &str) -> Result<std::vec::Vec<String>, Box<dyn std::error::Error>> {
-    let contents = fs::read_to_string(file_name)?;
-    Ok(contents.split("\n")
-        .map(|s| s.to_string())
-        .collect())
+pub mod grid;
+pub mod log;
+pub mod point;
+pub mod top_k;
+
+pub use grid::Grid;
+pub use point::{Direction, Point};
+pub use top_k::{top_k, TopK};
+
+pub fn read_input(path: &str) -> Result<String, Box<dyn std::error::Error>> {
+    if path == "-" {
+        let mut contents = String::new();
+        std::io::stdin().read_to_string(&mut contents)?;
+        return Ok(contents)
+    }
+
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(contents),
+        Err(e) => Err(Box::from(format!("Failed to read {}: {}", path, e)))
+    }
+}
+
+// Solver parameters from a small config file: one `name = value` per line, with blank
+// lines and # comments ignored. Values are checked later by the solver's set.
+pub fn parse_params(input: &str) -> Result<std::vec::Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let mut params = vec![];
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match line.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => params.push((name.trim().to_string(), value.trim().to_string())),
+            _ => return Err(Box::from(format!("line {}: expected name = value", i + 1)))
+        }
+    }
+    Ok(params)
+}
+
+// Drops a byte order mark, turns CRLF into LF, trims trailing whitespace from every line and
+// ends non-empty input with exactly one newline. Line numbers are unchanged, so parse errors
+// still point at the right place in the original file.
+pub fn normalize(input: &str) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+
+    let mut out = String::with_capacity(input.len() + 1);
+    for line in input.lines() {
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+
+    let len = out.trim_end().len();
+    out.truncate(len);
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
 }
 
-pub fn read_file(file_name: &str) -> Result<std::vec::Vec<String>, Box<dyn std::error::Error>> {
-    let contents = fs::read_to_string(file_name)?;
-    Ok(contents.split("\n")
-        .filter(|x| !x.is_empty())
-        .map(|s| s.to_string())
-        .collect())
+pub fn lines(input: &str) -> std::vec::Vec<&str> {
+    input.lines().collect()
 }
 
+// A run of non-blank lines, such as one elf's calories or one monkey.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block<'a> {
+    pub line: usize,
+    pub lines: std::vec::Vec<&'a str>
+}
+
+impl<'a> Block<'a> {
+    pub fn numbered(&self) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        self.lines.iter().enumerate().map(|(i, l)| (self.line + i, *l))
+    }
+}
+
+pub fn blocks(input: &str) -> std::vec::Vec<Block<'_>> {
+    let mut blocks: std::vec::Vec<Block> = vec![];
+    let mut in_block = false;
+
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            in_block = false;
+        }
+        else if in_block {
+            blocks.last_mut().unwrap().lines.push(line);
+        }
+        else {
+            blocks.push(Block { line: i + 1, lines: vec![line] });
+            in_block = true;
+        }
+    }
+
+    blocks
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String
+}
+
+impl ParseError {
+    pub fn new(day: u32, line: usize, column: usize, text: &str, expected: &str) -> ParseError {
+        ParseError {
+            day,
+            line,
+            column,
+            text: text.to_string(),
+            expected: expected.to_string()
+        }
+    }
+
+    pub fn at(day: u32, line: usize, text: &str, token: &str, expected: &str) -> ParseError {
+        ParseError::new(day, line, column_of(text, token), text, expected)
+    }
+
+    pub fn render(&self) -> String {
+        let gutter = self.line.to_string();
+        format!("{}\n {} | {}\n {} | {}^",
+            self,
+            gutter,
+            self.text,
+            " ".repeat(gutter.len()),
+            " ".repeat(self.column.saturating_sub(1)))
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "day {}, line {}, column {}: expected {}", self.day, self.line, self.column, self.expected)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub fn column_of(line: &str, token: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let pos = token.as_ptr() as usize;
+    if pos < start || pos > start + line.len() {
+        return 1
+    }
+
+    line[..pos - start].chars().count() + 1
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_column_of() {
+        let line = "60-71,59-70";
+        let (_, rhs) = line.split_once(',').unwrap();
+        assert_eq!(column_of(line, line), 1);
+        assert_eq!(column_of(line, rhs), 7);
+        assert_eq!(column_of(line, &line[line.len()..]), 12);
+        assert_eq!(column_of(line, &String::from("elsewhere")), 1);
+    }
+
+    #[test]
+    fn test_parse_params() {
+        let params = parse_params("# tournament rules\nwin = 10\n\n  lose=-1 \n").unwrap();
+        assert_eq!(params, vec![("win".to_string(), "10".to_string()), ("lose".to_string(), "-1".to_string())]);
+        assert_eq!(parse_params("win 10").unwrap_err().to_string(), "line 1: expected name = value");
+        assert!(parse_params("= 3").is_err());
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("\u{feff}1\r\n2  \r\n\r\n3"), "1\n2\n\n3\n");
+        assert_eq!(normalize("a\n\n\n"), "a\n");
+        assert_eq!(normalize("  [D]    \n 1   2 \n"), "  [D]\n 1   2\n");
+        assert_eq!(normalize(" \r\n"), "");
+    }
+
+    #[test]
+    fn test_blocks() {
+        let input = normalize("\n1\n2\r\n\r\n\r\n3\n");
+        assert_eq!(lines(&input), vec!["", "1", "2", "", "", "3"]);
+
+        let blocks = blocks(&input);
+        assert_eq!(blocks, vec![Block { line: 2, lines: vec!["1", "2"] }, Block { line: 6, lines: vec!["3"] }]);
+        assert_eq!(blocks[0].numbered().collect::<std::vec::Vec<_>>(), vec![(2, "1"), (3, "2")]);
+    }
+
+    #[test]
+    fn test_render() {
+        let err = ParseError::new(4, 12, 3, "60x71,59-70", "'-'");
+        assert_eq!(err.to_string(), "day 4, line 12, column 3: expected '-'");
+        assert_eq!(err.render(),
+            "day 4, line 12, column 3: expected '-'\n 12 | 60x71,59-70\n    |   ^");
+    }
+}
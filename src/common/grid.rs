@@ -0,0 +1,219 @@
+use super::point::{Direction, Point};
+use super::{lines, ParseError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: std::vec::Vec<T>
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height]
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn parse<F>(day: u32, input: &str, expected: &str, mut cell: F) -> Result<Grid<T>, ParseError>
+        where F: FnMut(char) -> Option<T>
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = vec![];
+
+        for (i, line) in lines(input).iter().enumerate().filter(|(_, l)| !l.is_empty()) {
+            for (col, c) in line.char_indices() {
+                match cell(c) {
+                    Some(v) => cells.push(v),
+                    None => return Err(ParseError::at(day, i + 1, line, &line[col..], expected))
+                }
+            }
+
+            let len = line.chars().count();
+            match width {
+                None => width = Some(len),
+                Some(w) if w != len => return Err(ParseError::new(day, i + 1, 1, line, &format!("a row of width {}", w))),
+                Some(_) => ()
+            }
+            height += 1;
+        }
+
+        match width {
+            None => Err(ParseError::new(day, 1, 1, "", "at least one row")),
+            Some(width) => Ok(Grid { width, height, cells })
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.y as usize) < self.height && (p.x as usize) < self.width
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.height && col < self.width {
+            Some(&self.cells[row * self.width + col])
+        }
+        else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        if row < self.height && col < self.width {
+            Some(&mut self.cells[row * self.width + col])
+        }
+        else {
+            None
+        }
+    }
+
+    pub fn at(&self, p: Point) -> Option<&T> {
+        if !self.contains(p) {
+            return None
+        }
+
+        self.get(p.y as usize, p.x as usize)
+    }
+
+    pub fn at_mut(&mut self, p: Point) -> Option<&mut T> {
+        if !self.contains(p) {
+            return None
+        }
+
+        self.get_mut(p.y as usize, p.x as usize)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |r| (0..width).map(move |c| (r, c)))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        self.positions().map(|(r, c)| Point::new(c as i32, r as i32))
+    }
+
+    pub fn row(&self, row: usize) -> impl DoubleEndedIterator<Item = &T> {
+        self.cells[row * self.width..(row + 1) * self.width].iter()
+    }
+
+    pub fn col(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> {
+        self.cells[col..].iter().step_by(self.width).take(self.height)
+    }
+
+    pub fn ray(&self, row: usize, col: usize, dir: Direction) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let start = Point::new(col as i32, row as i32);
+        std::iter::successors(Some(start.step(dir)), move |p| Some(p.step(dir)))
+            .take_while(move |&p| self.contains(p))
+            .map(|p| (p.y as usize, p.x as usize))
+    }
+
+    pub fn neighbours4(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(row, col, &Direction::ORTHOGONAL)
+    }
+
+    pub fn neighbours8(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbours(row, col, &Direction::ALL)
+    }
+
+    fn neighbours<'a>(&'a self, row: usize, col: usize, dirs: &'a [Direction]) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let p = Point::new(col as i32, row as i32);
+        dirs.iter()
+            .map(move |&d| p.step(d))
+            .filter(move |&n| self.contains(n))
+            .map(|n| (n.y as usize, n.x as usize))
+    }
+
+    pub fn render<F>(&self, mut cell: F) -> std::vec::Vec<String>
+        where F: FnMut(&T) -> char
+    {
+        (0..self.height).map(|r| self.row(r).map(&mut cell).collect()).collect()
+    }
+}
+
+impl<T> std::ops::Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        self.get(row, col).expect("grid position out of bounds")
+    }
+}
+
+impl<T> std::ops::IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        self.get_mut(row, col).expect("grid position out of bounds")
+    }
+}
+
+impl<T> std::ops::Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.at(p).expect("grid position out of bounds")
+    }
+}
+
+impl<T> std::ops::IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        self.at_mut(p).expect("grid position out of bounds")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn digits() -> Grid<u8> {
+        Grid::parse(0, "123\n456\n", "a digit", |c| c.to_digit(10).map(|d| d as u8)).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], 6);
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.get(0, 3), None);
+        assert_eq!(grid[Point::new(2, 0)], 3);
+        assert_eq!(grid.at(Point::new(-1, 0)), None);
+
+        let parse = |s| Grid::parse(0, s, "a digit", |c| c.to_digit(10));
+        assert_eq!(parse("12\n3x").unwrap_err(), ParseError::new(0, 2, 2, "3x", "a digit"));
+        assert_eq!(parse("12\n345").unwrap_err(), ParseError::new(0, 2, 1, "345", "a row of width 2"));
+        assert_eq!(parse("\n").unwrap_err(), ParseError::new(0, 1, 1, "", "at least one row"));
+    }
+
+    #[test]
+    fn test_iterators() {
+        let grid = digits();
+        assert_eq!(grid.row(1).copied().collect::<std::vec::Vec<_>>(), vec![4, 5, 6]);
+        assert_eq!(grid.col(1).rev().copied().collect::<std::vec::Vec<_>>(), vec![5, 2]);
+        assert_eq!(grid.ray(1, 2, Direction::Left).collect::<std::vec::Vec<_>>(), vec![(1, 1), (1, 0)]);
+        assert_eq!(grid.ray(0, 0, Direction::Up).count(), 0);
+        assert_eq!(grid.neighbours4(0, 0).collect::<std::vec::Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours8(0, 1).count(), 5);
+        assert_eq!(grid.positions().count(), 6);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut grid = Grid::new(3, 2, false);
+        grid[(0, 1)] = true;
+        assert_eq!(grid.render(|&b| if b { '#' } else { '.' }), vec![".#.".to_owned(), "...".to_owned()]);
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Quiet,
+    Info,
+    Debug,
+    Trace
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+impl Level {
+    pub fn from_flag(flag: &str) -> Option<Level> {
+        match flag {
+            "-q" => Some(Level::Quiet),
+            "-v" => Some(Level::Debug),
+            "-vv" => Some(Level::Trace),
+            _ => None
+        }
+    }
+}
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn level() -> Level {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => Level::Quiet,
+        1 => Level::Info,
+        2 => Level::Debug,
+        _ => Level::Trace
+    }
+}
+
+pub fn enabled(level: Level) -> bool {
+    level <= self::level()
+}
+
+// Log output goes to stderr so it never mixes with answers on stdout.
+pub fn emit(level: Level, args: std::fmt::Arguments) {
+    match level {
+        Level::Debug => eprintln!("[debug] {}", args),
+        Level::Trace => eprintln!("[trace] {}", args),
+        _ => eprintln!("{}", args)
+    }
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::common::log::enabled($crate::common::log::Level::Info) {
+            $crate::common::log::emit($crate::common::log::Level::Info, format_args!($($arg)*));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::common::log::enabled($crate::common::log::Level::Debug) {
+            $crate::common::log::emit($crate::common::log::Level::Debug, format_args!($($arg)*));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::common::log::enabled($crate::common::log::Level::Trace) {
+            $crate::common::log::emit($crate::common::log::Level::Trace, format_args!($($arg)*));
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        assert_eq!(Level::from_flag("-q"), Some(Level::Quiet));
+        assert_eq!(Level::from_flag("-vv"), Some(Level::Trace));
+        assert_eq!(Level::from_flag("-x"), None);
+
+        assert_eq!(level(), Level::Info);
+        assert!(enabled(Level::Info));
+        assert!(!enabled(Level::Debug));
+    }
+}
@@ -0,0 +1,152 @@
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+// y grows downwards, so a point's (y, x) lines up with a grid's (row, col).
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight
+}
+
+impl Direction {
+    pub const ORTHOGONAL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+    pub const ALL: [Direction; 8] = [
+        Direction::Up, Direction::Down, Direction::Left, Direction::Right,
+        Direction::UpLeft, Direction::UpRight, Direction::DownLeft, Direction::DownRight];
+
+    pub fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+            Direction::Right => Point::new(1, 0),
+            Direction::UpLeft => Point::new(-1, -1),
+            Direction::UpRight => Point::new(1, -1),
+            Direction::DownLeft => Point::new(-1, 1),
+            Direction::DownRight => Point::new(1, 1)
+        }
+    }
+
+    pub fn from_letter(letter: &str) -> Option<Direction> {
+        match letter {
+            "U" => Some(Direction::Up),
+            "D" => Some(Direction::Down),
+            "L" => Some(Direction::Left),
+            "R" => Some(Direction::Right),
+            _ => None
+        }
+    }
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Point) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn chebyshev(&self, other: &Point) -> i32 {
+        std::cmp::max((self.x - other.x).abs(), (self.y - other.y).abs())
+    }
+
+    pub fn signum(&self) -> Point {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn clamp(&self, min: i32, max: i32) -> Point {
+        Point::new(self.x.clamp(min, max), self.y.clamp(min, max))
+    }
+
+    pub fn step(&self, dir: Direction) -> Point {
+        *self + dir.offset()
+    }
+
+    pub fn neighbours4(&self) -> impl Iterator<Item = Point> {
+        let p = *self;
+        Direction::ORTHOGONAL.into_iter().map(move |d| p.step(d))
+    }
+
+    pub fn neighbours8(&self) -> impl Iterator<Item = Point> {
+        let p = *self;
+        Direction::ALL.into_iter().map(move |d| p.step(d))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Point) {
+        *self = *self - rhs;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point::new(3, -2);
+        let b = Point::new(-1, 4);
+        assert_eq!(a + b, Point::new(2, 2));
+        assert_eq!(a - b, Point::new(4, -6));
+        assert_eq!(-a, Point::new(-3, 2));
+        assert_eq!((a - b).signum(), Point::new(1, -1));
+        assert_eq!((a - b).clamp(-1, 1), Point::new(1, -1));
+        assert_eq!(a.manhattan(&b), 10);
+        assert_eq!(a.chebyshev(&b), 6);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let p = Point::new(1, 1);
+        assert_eq!(p.step(Direction::Up), Point::new(1, 0));
+        assert_eq!(p.neighbours4().collect::<std::vec::Vec<_>>(),
+            vec![Point::new(1, 0), Point::new(1, 2), Point::new(0, 1), Point::new(2, 1)]);
+        assert!(p.neighbours8().all(|n| n.chebyshev(&p) == 1));
+        assert_eq!(p.neighbours8().count(), 8);
+    }
+}
@@ -0,0 +1,55 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+// Keeps the k largest items pushed so far in a min-heap, so memory stays at O(k)
+// however long the input is. Items are numbered in the order they were pushed.
+pub struct TopK<T: Ord> {
+    k: usize,
+    pushed: usize,
+    heap: BinaryHeap<Reverse<(T, Reverse<usize>)>>
+}
+
+impl<T: Ord> TopK<T> {
+    pub fn new(k: usize) -> TopK<T> {
+        // k can be far larger than the input, so the heap grows as items arrive instead.
+        TopK { k, pushed: 0, heap: BinaryHeap::with_capacity(k.min(1024) + 1) }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.heap.push(Reverse((item, Reverse(self.pushed))));
+        self.pushed += 1;
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+
+    // (index, item) pairs, largest first; equal items keep their input order.
+    pub fn into_sorted_vec(self) -> std::vec::Vec<(usize, T)> {
+        self.heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((item, Reverse(i)))| (i, item))
+            .collect()
+    }
+}
+
+pub fn top_k<T: Ord, I: IntoIterator<Item = T>>(items: I, k: usize) -> std::vec::Vec<(usize, T)> {
+    let mut top = TopK::new(k);
+    for item in items {
+        top.push(item);
+    }
+    top.into_sorted_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_top_k() {
+        assert_eq!(top_k([5, 3, 5, 7, 1, 9, 10], 3), vec![(6, 10), (5, 9), (3, 7)]);
+        assert_eq!(top_k([5, 3, 5], 2), vec![(0, 5), (2, 5)]);
+        assert_eq!(top_k([1, 2], 5), vec![(1, 2), (0, 1)]);
+        assert!(top_k([1, 2], 0).is_empty());
+        assert_eq!(top_k([1, 2], usize::MAX), vec![(1, 2), (0, 1)]);
+    }
+}
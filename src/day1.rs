@@ -1,67 +1,247 @@
-use std::fs;
+use crate::common::{blocks, top_k, ParseError, TopK};
+use crate::solver::{Answer, Part, Solver};
 
-pub fn run_part1() -> Result<(), Box<dyn std::error::Error>> {
-    let contents = fs::read_to_string("day1.txt")?;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elf {
+    pub total: u64,
+    pub items: usize
+}
 
-    let elves = parse_input(&contents)?;
-    let max = elves.iter().max().unwrap_or(&0);
-    println!("{}", max);
-    Ok(())
+#[derive(Default)]
+pub struct Solution {
+    pub top: Option<usize>
 }
 
-pub fn run_part2() -> Result<(), Box<dyn std::error::Error>> {
-    let contents = fs::read_to_string("day1.txt")?;
+impl Solver for Solution {
+    type Parsed = std::vec::Vec<Elf>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, elves: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        self.answer(Part::One, &top_elves(elves, self.top.unwrap_or(1)))
+    }
+
+    fn part2(&self, elves: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        self.answer(Part::Two, &top_elves(elves, self.top.unwrap_or(3)))
+    }
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+        match (name, value.parse::<usize>()) {
+            ("top", Ok(k)) if k > 0 => self.top = Some(k),
+            ("top", _) => return Err(Box::from("top expects a positive number")),
+            _ => return Err(Box::from(format!("Unknown parameter {}", name)))
+        }
+        Ok(())
+    }
+}
 
-    let elves = parse_input(&contents)?;
+impl Solution {
+    // How many of the top elves either part needs.
+    pub fn needed(&self) -> usize {
+        self.top.unwrap_or(3)
+    }
 
-    let top_three = find_top_three(elves);
-    let sum: i32 = top_three.iter().sum();
+    pub fn answer(&self, part: Part, top: &[(usize, u64)]) -> Result<Answer, Box<dyn std::error::Error>> {
+        match (part, self.top) {
+            (Part::One, None) => Answer::try_from(top.first().map_or(0, |&(_, total)| total)),
+            (Part::One, Some(k)) => Ok(Answer::Grid(top.iter().take(k).map(|(elf, total)| format!("Elf {}: {}", elf + 1, total)).collect())),
+            (Part::Two, _) => {
+                let k = self.needed();
+                let sum = top.iter()
+                    .take(k)
+                    .try_fold(0u64, |acc, &(_, total)| acc.checked_add(total))
+                    .ok_or_else(|| format!("Sum of the top {} elves overflowed", k))?;
+                Answer::try_from(sum)
+            }
+        }
+    }
+}
 
-    println!("{}", sum);
-    Ok(())
+// Adds one item to the running total of elf `elf` (numbered from 1 as in the answers).
+fn add_calories(total: u64, line: &str, line_no: usize, elf: usize) -> Result<u64, ParseError> {
+    match line.parse::<u64>() {
+        Ok(v) => match total.checked_add(v) {
+            Some(v) => Ok(v),
+            None => Err(ParseError::new(1, line_no, 1, line, &format!("a calorie count that keeps elf {}'s total in range", elf)))
+        },
+        Err(_) if line.parse::<i64>().is_ok() => Err(ParseError::new(1, line_no, 1, line, &format!("a non-negative calorie count for elf {}", elf))),
+        Err(_) if line.bytes().all(|b| b.is_ascii_digit()) => Err(ParseError::new(1, line_no, 1, line, &format!("a calorie count that fits in 64 bits for elf {}", elf))),
+        Err(_) => Err(ParseError::new(1, line_no, 1, line, &format!("a calorie count for elf {}", elf)))
+    }
 }
 
-fn parse_input(input: &str) -> Result<std::vec::Vec<i32>, Box<dyn std::error::Error>> {
+// Elves are separated by one or more blank lines, so a run of blank lines never yields an
+// elf with no items. An elf whose items are all zero is kept, with a total of 0.
+pub fn parse_input(input: &str) -> Result<std::vec::Vec<Elf>, ParseError> {
     let mut elves = vec![];
-    let cals = input.split("\n");
 
-    let mut curr = 0;
-    for x in cals {
-        if x.is_empty() {
-            if curr > 0 {
-                elves.push(curr);
+    for block in blocks(input) {
+        let mut curr = 0;
+        for (line_no, x) in block.numbered() {
+            curr = add_calories(curr, x, line_no, elves.len() + 1)?;
+        }
+        elves.push(Elf { total: curr, items: block.lines.len() });
+    }
+
+    Ok(elves)
+}
+
+#[derive(Debug)]
+pub struct Summary {
+    pub elves: usize,
+    pub calories: u64,
+    pub top: std::vec::Vec<(usize, u64)>
+}
+
+struct RunningTotals {
+    elves: usize,
+    calories: u64,
+    top: TopK<u64>
+}
+
+impl RunningTotals {
+    fn add(&mut self, total: u64) -> Result<(), Box<dyn std::error::Error>> {
+        self.elves += 1;
+        self.calories = self.calories.checked_add(total).ok_or("Total calories overflowed")?;
+        self.top.push(total);
+        Ok(())
+    }
+}
+
+// Reads the input a line at a time and keeps only running aggregates plus the k largest
+// totals, so inputs far bigger than memory can be summarized.
+pub fn summarize<R: std::io::BufRead>(reader: R, k: usize) -> Result<Summary, Box<dyn std::error::Error>> {
+    let mut totals = RunningTotals { elves: 0, calories: 0, top: TopK::new(k) };
+    let mut curr = None;
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = if i == 0 { line.trim_start_matches('\u{feff}') } else { &line }.trim_end();
+
+        if line.is_empty() {
+            if let Some(total) = curr.take() {
+                totals.add(total)?;
             }
-            curr = 0;
         }
         else {
-            curr += x.parse::<i32>()?;
+            curr = Some(add_calories(curr.unwrap_or(0), line, i + 1, totals.elves + 1)?);
         }
     }
 
-    if curr > 0 {
-        elves.push(curr);
+    if let Some(total) = curr {
+        totals.add(total)?;
     }
-    Ok(elves)
+
+    Ok(Summary { elves: totals.elves, calories: totals.calories, top: totals.top.into_sorted_vec() })
 }
 
-fn find_top_three(input: std::vec::Vec<i32>) -> std::vec::Vec<i32> {
-    let mut top = vec![];
+// The k elves carrying the most calories as (elf index, total), most first.
+pub fn top_elves(elves: &[Elf], k: usize) -> std::vec::Vec<(usize, u64)> {
+    top_k(elves.iter().map(|e| e.total), k)
+}
 
-    for x in &input {
-        let pos = top.partition_point(|v| *v > *x);
-        if pos < 3 {
-            top.insert(pos, *x);
-            if top.len() > 3 {
-                top.pop();
-            }
+pub struct Bucket {
+    pub from: u64,
+    pub to: u64,
+    pub count: usize
+}
+
+pub struct Stats {
+    pub count: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub stddev: f64,
+    pub percentiles: std::vec::Vec<(u32, u64)>,
+    pub histogram: std::vec::Vec<Bucket>,
+    pub mean_items: f64,
+    pub min_items: usize,
+    pub max_items: usize,
+    pub most_items: (usize, usize)
+}
+
+const PERCENTILES: [u32; 6] = [10, 25, 50, 75, 90, 99];
+const HISTOGRAM_BUCKETS: usize = 10;
+const HISTOGRAM_WIDTH: usize = 40;
+
+impl Stats {
+    pub fn new(elves: &[Elf]) -> Option<Stats> {
+        if elves.is_empty() {
+            return None
+        }
+
+        let mut totals: std::vec::Vec<u64> = elves.iter().map(|e| e.total).collect();
+        totals.sort();
+
+        let n = totals.len();
+        let mean = totals.iter().map(|&t| t as f64).sum::<f64>() / n as f64;
+        let median = if n % 2 == 1 {
+            totals[n / 2] as f64
+        }
+        else {
+            (totals[n / 2 - 1] as f64 + totals[n / 2] as f64) / 2.0
+        };
+        let variance = totals.iter().map(|&t| (t as f64 - mean).powi(2)).sum::<f64>() / n as f64;
+
+        // Nearest-rank percentiles, so every reported value is an actual elf's total.
+        let percentiles = PERCENTILES.iter()
+            .map(|&p| (p, totals[((p as usize * n).div_ceil(100)).max(1) - 1]))
+            .collect();
+
+        let (min, max) = (totals[0], totals[n - 1]);
+        let width = (max - min) / HISTOGRAM_BUCKETS as u64 + 1;
+        let mut histogram: std::vec::Vec<Bucket> = (0..HISTOGRAM_BUCKETS as u64)
+            .map_while(|b| b.checked_mul(width).and_then(|offset| min.checked_add(offset)))
+            .take_while(|&from| from <= max)
+            .map(|from| Bucket { from, to: from.saturating_add(width - 1).min(max), count: 0 })
+            .collect();
+        for &t in &totals {
+            histogram[((t - min) / width) as usize].count += 1;
         }
+
+        let most_items = elves.iter().enumerate()
+            .fold((0, 0), |best, (i, e)| if e.items > best.1 { (i, e.items) } else { best });
+
+        Some(Stats {
+            count: n,
+            mean,
+            median,
+            stddev: variance.sqrt(),
+            percentiles,
+            histogram,
+            mean_items: elves.iter().map(|e| e.items).sum::<usize>() as f64 / n as f64,
+            min_items: elves.iter().map(|e| e.items).min().unwrap_or(0),
+            max_items: elves.iter().map(|e| e.items).max().unwrap_or(0),
+            most_items
+        })
     }
 
-    top
+    pub fn render(&self) -> std::vec::Vec<String> {
+        let mut out = vec![
+            format!("Elves:         {}", self.count),
+            format!("Calories:      mean {:.1}, median {:.1}, stddev {:.1}", self.mean, self.median, self.stddev),
+            format!("Percentiles:   {}", self.percentiles.iter().map(|(p, v)| format!("p{} {}", p, v)).collect::<std::vec::Vec<_>>().join(", ")),
+            format!("Items per elf: min {}, mean {:.1}, max {}", self.min_items, self.mean_items, self.max_items),
+            format!("Most items:    elf {} ({} items)", self.most_items.0 + 1, self.most_items.1),
+            "Histogram:".to_string()
+        ];
+
+        let tallest = self.histogram.iter().map(|b| b.count).max().unwrap_or(1);
+        let label_width = self.histogram.last().map_or(1, |b| b.to.to_string().len());
+        for bucket in &self.histogram {
+            let bar = "#".repeat((bucket.count * HISTOGRAM_WIDTH).div_ceil(tallest));
+            out.push(format!("  {:>w$} - {:>w$} | {:<hw$} {}", bucket.from, bucket.to, bar, bucket.count, w = label_width, hw = HISTOGRAM_WIDTH));
+        }
+
+        out
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use super::*;
+
     #[test]
     fn test_parse_input() {
         let input = 
@@ -77,19 +257,80 @@ r#"
 8
 9
 1"#;
-        let output = super::parse_input(input).unwrap();
-        for x in &output  { println!("X: {} ", x); }
+        let output = parse_input(input).unwrap();
         assert_eq!(output.len(), 3);
-        assert_eq!(output[0], 6);
-        assert_eq!(output[1], 18);
-        assert_eq!(output[2], 18);
+        assert_eq!(output[0], Elf { total: 6, items: 3 });
+        assert_eq!(output[1].total, 18);
+        assert_eq!(output[2].total, 18);
+
+        let output = parse_input("0\n0\n\n\n\n4294967296\n4294967296\n").unwrap();
+        assert_eq!(output, vec![Elf { total: 0, items: 2 }, Elf { total: 8589934592, items: 2 }]);
+        assert_eq!(Solution::default().part1(&output).unwrap(), Answer::Int(8589934592));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_input("1\n\n2\n-3\n").unwrap_err();
+        assert_eq!(err.line, 4);
+        assert_eq!(err.to_string(), "day 1, line 4, column 1: expected a non-negative calorie count for elf 2");
+
+        let err = parse_input("1\n\n\n18446744073709551615\n1\n").unwrap_err();
+        assert_eq!(err.line, 5);
+        assert!(err.expected.contains("elf 2's total"), "{}", err);
+
+        let err = parse_input("99999999999999999999\n").unwrap_err();
+        assert!(err.expected.contains("64 bits for elf 1"), "{}", err);
+        assert!(parse_input("12a\n").is_err());
+
+        let err = summarize("1\n\n-1\n".as_bytes(), 1).unwrap_err();
+        assert!(err.to_string().contains("line 3") && err.to_string().contains("elf 2"), "{}", err);
+        assert!(Solution::default().part2(&elves(&[u64::MAX, 1])).is_err());
+    }
+
+    fn elves(totals: &[u64]) -> std::vec::Vec<Elf> {
+        totals.iter().map(|&total| Elf { total, items: 1 }).collect()
+    }
+
+    #[test]
+    fn test_top_elves() {
+        let output = top_elves(&elves(&[5, 3, 5, 7, 1, 9, 10]), 3);
+        assert_eq!(output, vec![(6, 10), (5, 9), (3, 7)]);
+
+        let solution = Solution { top: Some(2) };
+        let answer = solution.part1(&elves(&[5, 3, 5, 7])).unwrap();
+        assert_eq!(answer, Answer::Grid(vec!["Elf 4: 7".to_string(), "Elf 1: 5".to_string()]));
+        assert_eq!(solution.part2(&elves(&[5, 3, 5, 7])).unwrap(), Answer::Int(12));
+    }
+
+    #[test]
+    fn test_stats() {
+        let elves = parse_input("1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n").unwrap();
+        let stats = Stats::new(&elves).unwrap();
+        assert_eq!(stats.count, 5);
+        assert_eq!(stats.mean, 11000.0);
+        assert_eq!(stats.median, 10000.0);
+        assert!((stats.stddev - 6985.7).abs() < 0.1);
+        assert_eq!(stats.percentiles[0], (10, 4000));
+        assert_eq!(stats.percentiles[stats.percentiles.len() - 1], (99, 24000));
+        assert_eq!(stats.most_items, (0, 3));
+        assert_eq!(stats.histogram.iter().map(|b| b.count).sum::<usize>(), 5);
+        assert_eq!((stats.histogram[0].from, stats.histogram[0].count), (4000, 2));
+
+        let report = stats.render();
+        assert!(report.contains(&"Most items:    elf 1 (3 items)".to_string()), "{:?}", report);
+        assert!(Stats::new(&[]).is_none());
     }
 
     #[test]
-    fn test_top_three() {
+    fn test_summarize() {
+        let input = "\u{feff}1\r\n2\r\n\r\n\r\n7  \n\n3\n";
+        let summary = summarize(input.as_bytes(), 2).unwrap();
+        assert_eq!((summary.elves, summary.calories), (3, 13));
+        assert_eq!(summary.top, vec![(1, 7), (0, 3)]);
+        assert_eq!(summary.top, top_elves(&parse_input(&crate::common::normalize(input)).unwrap(), 2));
 
-        let output = super::find_top_three(vec![5, 3, 5, 7, 1, 9, 10]);
-        assert_eq!(output, vec![10, 9, 7]);
+        let err = summarize("1\n\nx\n".as_bytes(), 2).unwrap_err();
+        assert_eq!(err.downcast_ref::<ParseError>().map(|e| e.line), Some(3));
     }
 }
 
@@ -1,255 +1,156 @@
-use crate::common::read_file;
+use crate::common::{lines, Grid, ParseError};
+use crate::solver::{Answer, Solver};
 
-struct CPU {
-    cycle: i32,
-    reg_x: i32,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Noop,
+    Addx(i32)
+}
+
+pub struct Cpu {
+    pub cycle: i32,
+    pub reg_x: i32,
 
-    signal_strength_sum: i32,
-    output_crt: bool,
-    crt: std::vec::Vec<String>
+    pub signal_strength_sum: i32,
+    pub output_crt: bool,
+    pub crt: Grid<bool>
 }
 
-impl CPU {
-    fn new() -> CPU {
-        CPU {
+const CRT_WIDTH: usize = 40;
+const CRT_HEIGHT: usize = 6;
+
+impl Default for Cpu {
+    fn default() -> Cpu {
+        Cpu::new()
+    }
+}
+
+impl Cpu {
+    pub fn new() -> Cpu {
+        Cpu {
             cycle: 0,
             reg_x: 1,
             signal_strength_sum: 0,
 
             output_crt: false,
-            crt: vec![]
+            crt: Grid::new(CRT_WIDTH, CRT_HEIGHT, false)
         }
     }
 
-    fn cycle(&mut self) {
+    fn cycle(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if self.output_crt {
-            let drawing_pixel_pos = (self.cycle % 40) + 1;
-            if drawing_pixel_pos == 1 {
-                self.crt.push(String::new());
+            let row = self.cycle as usize / CRT_WIDTH;
+            let col = self.cycle as usize % CRT_WIDTH;
+            if let Some(pixel) = self.crt.get_mut(row, col) {
+                *pixel = (self.reg_x.saturating_sub(1)..=self.reg_x.saturating_add(1)).contains(&(col as i32));
             }
-
-            let row = self.crt.last_mut().unwrap();
-            let draw_pixel = (self.reg_x..self.reg_x + 3).contains(&drawing_pixel_pos);
-            row.push(if draw_pixel { '#' } else { '.' });
         }
 
-        self.cycle += 1;
+        self.cycle = self.cycle.checked_add(1).ok_or("Cycle counter overflowed")?;
         if self.cycle > 19 {
             let rel = self.cycle - 20;
             if (rel % 40) == 0 {
-                self.signal_strength_sum += self.cycle * self.reg_x;
+                self.signal_strength_sum = self.cycle.checked_mul(self.reg_x)
+                    .and_then(|strength| self.signal_strength_sum.checked_add(strength))
+                    .ok_or_else(|| format!("Signal strength overflowed at cycle {}", self.cycle))?;
             }
         }
+        Ok(())
     }
 
-    fn noop(&mut self) {
-        self.cycle();
+    pub fn noop(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.cycle()
     }
 
-    fn add(&mut self, val: i32) {
-        self.cycle();
-        self.cycle();
-        self.reg_x += val;
+    pub fn add(&mut self, val: i32) -> Result<(), Box<dyn std::error::Error>> {
+        self.cycle()?;
+        self.cycle()?;
+        self.reg_x = self.reg_x.checked_add(val).ok_or_else(|| format!("Register X overflowed at cycle {}", self.cycle))?;
+        Ok(())
     }
 }
 
-fn run(cpu: &mut CPU, lines: &std::vec::Vec<String>) -> Result<i32, Box<dyn std::error::Error>> {
-    for ln in lines {
-        match &ln[..4] {
-            "noop" => cpu.noop(),
-            "addx" => {
-                let val: i32 = ln[5..].parse()?;
-                cpu.add(val);
+pub fn parse_program(lines: &[&str]) -> Result<std::vec::Vec<Instruction>, ParseError> {
+    let mut program = vec![];
+    for (i, &ln) in lines.iter().enumerate().filter(|(_, l)| !l.is_empty()) {
+        let instruction = match ln.split_once(' ') {
+            None if ln == "noop" => Instruction::Noop,
+            Some(("addx", val)) => match val.parse() {
+                Ok(v) => Instruction::Addx(v),
+                Err(_) => return Err(ParseError::at(10, i + 1, ln, val, "an integer"))
             },
-            cmd => return Err(Box::from(format!("Unknown command {}", cmd)))
+            _ => return Err(ParseError::new(10, i + 1, 1, ln, "'noop' or 'addx <value>'"))
+        };
+        program.push(instruction);
+    }
+
+    Ok(program)
+}
+
+pub fn run(cpu: &mut Cpu, program: &[Instruction]) -> Result<i32, Box<dyn std::error::Error>> {
+    for instruction in program {
+        match instruction {
+            Instruction::Noop => cpu.noop()?,
+            Instruction::Addx(val) => cpu.add(*val)?
         };
     }
     
     Ok(cpu.signal_strength_sum)
 }
 
-pub fn run_part1() -> Result<(), Box<dyn std::error::Error>> {
-    let mut cpu = CPU::new();
-    let lines = read_file("day10.txt")?;
-    let strength_sum = run(&mut cpu, &lines)?;
-
-    println!("Strength sum: {}", strength_sum);
-    Ok(())
+pub fn render_crt(crt: &Grid<bool>) -> std::vec::Vec<String> {
+    crt.render(|&lit| if lit { '#' } else { '.' })
 }
 
-pub fn run_part2() -> Result<(), Box<dyn std::error::Error>> {
-    let mut cpu = CPU::new();
-    cpu.output_crt = true;
+#[derive(Default)]
+pub struct Solution;
+
+impl Solver for Solution {
+    type Parsed = std::vec::Vec<Instruction>;
 
-    let lines = read_file("day10.txt")?;
-    run(&mut cpu, &lines)?;
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>> {
+        Ok(parse_program(&lines(input))?)
+    }
+
+    fn part1(&self, program: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let mut cpu = Cpu::new();
+        let strength_sum = run(&mut cpu, program)?;
+
+        Ok(Answer::from(strength_sum))
+    }
+
+    fn part2(&self, program: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let mut cpu = Cpu::new();
+        cpu.output_crt = true;
+
+        run(&mut cpu, program)?;
 
-    for row in cpu.crt {
-        println!("{}", row);
+        Ok(Answer::Grid(render_crt(&cpu.crt)))
     }
-    Ok(())
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_parse_program() {
+        assert_eq!(parse_program(&lines("noop\naddx -3\n")).unwrap(), vec![Instruction::Noop, Instruction::Addx(-3)]);
+        assert_eq!(parse_program(&lines("noop\naddx x")).unwrap_err(), ParseError::new(10, 2, 6, "addx x", "an integer"));
+        assert_eq!(parse_program(&lines("nop")).unwrap_err(), ParseError::new(10, 1, 1, "nop", "'noop' or 'addx <value>'"));
+    }
+
     #[test]
     fn test_run() {
-        let content =
-r#"addx 15
-addx -11
-addx 6
-addx -3
-addx 5
-addx -1
-addx -8
-addx 13
-addx 4
-noop
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx -35
-addx 1
-addx 24
-addx -19
-addx 1
-addx 16
-addx -11
-noop
-noop
-addx 21
-addx -15
-noop
-noop
-addx -3
-addx 9
-addx 1
-addx -3
-addx 8
-addx 1
-addx 5
-noop
-noop
-noop
-noop
-noop
-addx -36
-noop
-addx 1
-addx 7
-noop
-noop
-noop
-addx 2
-addx 6
-noop
-noop
-noop
-noop
-noop
-addx 1
-noop
-noop
-addx 7
-addx 1
-noop
-addx -13
-addx 13
-addx 7
-noop
-addx 1
-addx -33
-noop
-noop
-noop
-addx 2
-noop
-noop
-noop
-addx 8
-noop
-addx -1
-addx 2
-addx 1
-noop
-addx 17
-addx -9
-addx 1
-addx 1
-addx -3
-addx 11
-noop
-noop
-addx 1
-noop
-addx 1
-noop
-noop
-addx -13
-addx -19
-addx 1
-addx 3
-addx 26
-addx -30
-addx 12
-addx -1
-addx 3
-addx 1
-noop
-noop
-noop
-addx -9
-addx 18
-addx 1
-addx 2
-noop
-noop
-addx 9
-noop
-noop
-noop
-addx -1
-addx 2
-addx -37
-addx 1
-addx 3
-noop
-addx 15
-addx -21
-addx 22
-addx -6
-addx 1
-noop
-addx 2
-addx 1
-noop
-addx -10
-noop
-noop
-addx 20
-addx 1
-addx 2
-addx 2
-addx -6
-addx -11
-noop
-noop
-noop"#;
-
-        let mut cpu = CPU::new();
+        let content = crate::examples::for_day(10).next().unwrap().input;
+
+        let mut cpu = Cpu::new();
         cpu.output_crt = true;
-        let val = match run(&mut cpu, &content.split("\n").map(|l| l.to_owned()).collect()) {
+        let program = match parse_program(&lines(content)) {
             Err(x) => panic!("{}", x),
             Ok(v) => v
         };
+        let val = run(&mut cpu, &program).unwrap();
 
         assert_eq!(val, 13140);
 
@@ -261,9 +162,15 @@ r#"##..##..##..##..##..##..##..##..##..##..
 ######......######......######......####
 #######.......#######.......#######....."#.split('\n').collect();
 
-        for (a, b) in itertools::zip_eq(expected_crt, cpu.crt) {
+        for (a, b) in itertools::zip_eq(expected_crt, render_crt(&cpu.crt)) {
             assert_eq!(a, b);
         }
     }
+
+    #[test]
+    fn test_overflow() {
+        let program = vec![Instruction::Addx(i32::MAX), Instruction::Addx(1)];
+        assert!(run(&mut Cpu::new(), &program).is_err());
+    }
 }
 
@@ -1,30 +1,30 @@
-use crate::common::read_file2;
-use nom;
+use crate::common::{blocks, ParseError};
+use crate::solver::{Answer, Solver};
 
 #[derive(Debug, Clone, Copy)]
-enum Op {
+pub enum Op {
     Mul(u64),
     Add(u64),
     Square
 }
 
 impl Op {
-    fn execute(&self, val: u64) -> u64 {
+    pub fn execute(&self, val: u64) -> Option<u64> {
         match self {
-            Op::Mul(x) => val * x,
-            Op::Add(x) => val + x,
-            Op::Square => val * val
+            Op::Mul(x) => val.checked_mul(*x),
+            Op::Add(x) => val.checked_add(*x),
+            Op::Square => val.checked_mul(val)
         }
     }
 }
 
-enum WorryReducer {
+pub enum WorryReducer {
     Div3,
     Mod(u64)
 }
 
 impl WorryReducer {
-    fn execute(&self, val: u64) -> u64 {
+    pub fn execute(&self, val: u64) -> u64 {
         match self {
             WorryReducer::Div3 => val / 3,
             WorryReducer::Mod(m) => val % m
@@ -32,22 +32,36 @@ impl WorryReducer {
     }
 }
 
-#[derive(Debug)]
-struct Monkey {
-    items: std::vec::Vec<u64>,
-    op: Op,
-    div: u64,
-    true_monkey: usize,
-    false_monkey: usize,
-    throw_count: u64
+#[derive(Debug, Clone)]
+pub struct Monkey {
+    pub items: std::vec::Vec<u64>,
+    pub op: Op,
+    pub div: u64,
+    pub true_monkey: usize,
+    pub false_monkey: usize,
+    pub throw_count: u64
 }
 
-fn parse(lines: &std::vec::Vec<String>) -> Result<std::vec::Vec<Monkey>, Box<dyn std::error::Error>> {
+fn expect<T>(result: nom::IResult<&str, T>, line: &str, line_no: usize, expected: &str) -> Result<T, ParseError> {
+    let rest = match result {
+        Ok(("", v)) => return Ok(v),
+        Ok((rest, _)) => rest,
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => e.input,
+        Err(nom::Err::Incomplete(_)) => ""
+    };
+
+    Err(ParseError::new(11, line_no, line[..line.len() - rest.len()].chars().count() + 1, line, expected))
+}
+
+pub fn parse(input: &str) -> Result<std::vec::Vec<Monkey>, ParseError> {
     let mut monkeys = vec![];
+    let mut targets = vec![];
 
     use nom::{
         sequence::preceded,
+        sequence::terminated,
         sequence::tuple,
+        character::complete::space0,
         character::complete::space1,
         character::complete::digit1,
         bytes::complete::tag,
@@ -57,12 +71,21 @@ fn parse(lines: &std::vec::Vec<String>) -> Result<std::vec::Vec<Monkey>, Box<dyn
         IResult
     };
 
-    for text in lines.chunks(7) {
+    for block in blocks(input) {
+        let base = block.line - 1;
+        let line = |i: usize| block.lines.get(i).copied().unwrap_or("");
+
+        let header: IResult<&str, &str> = terminated(
+            preceded(tag("Monkey "), digit1),
+            tag(":")
+        )(line(0));
+        expect(header, line(0), base + 1, "'Monkey <n>:'")?;
+
         let items: IResult<&str, std::vec::Vec<u64>> = preceded(
-            tuple((space1, tag("Starting items:"), space1)),
-            separated_list1(tag(", "), map_res(digit1, |s| u64::from_str_radix(s, 10)))
-        )(&text[1]);
-        let items = items.unwrap().1;
+            tuple((space1, tag("Starting items:"), space0)),
+            separated_list1(tag(", "), map_res(digit1, |s: &str| s.parse::<u64>()))
+        )(line(1));
+        let items = expect(items, line(1), base + 2, "'Starting items: <n>, <n>, ...'")?;
 
         let op: IResult<&str, Op> = preceded(
             tuple((space1, tag("Operation: new = old"), space1)),
@@ -75,31 +98,40 @@ fn parse(lines: &std::vec::Vec<String>) -> Result<std::vec::Vec<Monkey>, Box<dyn
                 |(op_code, _space, val)|
                     match (op_code, val) {
                         ("*", "old") => Ok(Op::Square),
-                        ("*", _) => Ok(Op::Mul(val.parse().unwrap())),
-                        ("+", _) => Ok(Op::Add(val.parse().unwrap())),
+                        ("*", _) => val.parse().map(Op::Mul).map_err(|e| e.to_string()),
+                        ("+", _) => val.parse().map(Op::Add).map_err(|e| e.to_string()),
                         _ => Err(format!("Unknown op {}", op_code))
                     }
                 ) 
-        )(&text[2]);
-        let op = op.unwrap().1;
+        )(line(2));
+        let op = expect(op, line(2), base + 3, "'Operation: new = old <*|+> <n|old>'")?;
 
         let div: IResult<&str, u64> = preceded(
             tuple((space1, tag("Test: divisible by"), space1)),
-            map_res(digit1, |s| u64::from_str_radix(s, 10))
-        )(&text[3]);
-        let div = div.unwrap().1;
+            map_res(digit1, |s: &str| s.parse::<u64>())
+        )(line(3));
+        let div = expect(div, line(3), base + 4, "'Test: divisible by <n>'")?;
+        if div == 0 {
+            return Err(ParseError::new(11, base + 4, line(3).len(), line(3), "a non-zero divisor"));
+        }
 
         let true_monkey: IResult<&str, usize> = preceded(
             tuple((space1, tag("If true: throw to monkey"), space1)),
-            map_res(digit1, |s| usize::from_str_radix(s, 10))
-        )(&text[4]);
-        let true_monkey = true_monkey.unwrap().1;
+            map_res(digit1, |s: &str| s.parse::<usize>())
+        )(line(4));
+        let true_monkey = expect(true_monkey, line(4), base + 5, "'If true: throw to monkey <n>'")?;
         
         let false_monkey: IResult<&str, usize> = preceded(
             tuple((space1, tag("If false: throw to monkey"), space1)),
-            map_res(digit1, |s| usize::from_str_radix(s, 10))
-        )(&text[5]);
-        let false_monkey = false_monkey.unwrap().1;
+            map_res(digit1, |s: &str| s.parse::<usize>())
+        )(line(5));
+        let false_monkey = expect(false_monkey, line(5), base + 6, "'If false: throw to monkey <n>'")?;
+        targets.push((base + 5, line(4), true_monkey));
+        targets.push((base + 6, line(5), false_monkey));
+
+        if block.lines.len() > 6 {
+            return Err(ParseError::new(11, base + 7, 1, line(6), "a blank line before the next monkey"));
+        }
 
         monkeys.push(Monkey {
             items,
@@ -111,21 +143,26 @@ fn parse(lines: &std::vec::Vec<String>) -> Result<std::vec::Vec<Monkey>, Box<dyn
         });
     }
 
-    Ok(monkeys)
-}
+    if monkeys.is_empty() {
+        return Err(ParseError::new(11, 1, 1, "", "'Monkey <n>:'"));
+    }
+
+    for (line_no, line, target) in targets {
+        if target >= monkeys.len() {
+            return Err(ParseError::new(11, line_no, line.len(), line, &format!("a monkey below {}", monkeys.len())));
+        }
+    }
 
-fn load_monkeys() -> Result<std::vec::Vec<Monkey>, Box<dyn std::error::Error>> {
-    let lines = read_file2("day11.txt")?;
-    Ok(parse(&lines)?)
+    Ok(monkeys)
 }
 
-fn run_monkeys(mut monkeys: std::vec::Vec<Monkey>, worry_reducer: WorryReducer, rounds: usize) {
+pub fn run_monkeys(mut monkeys: std::vec::Vec<Monkey>, worry_reducer: WorryReducer, rounds: usize) -> Result<u64, Box<dyn std::error::Error>> {
     for _ in 0..rounds {
-        for m in 0..monkeys.len() {
-            monkeys[m].throw_count += monkeys[m].items.len() as u64;
+        for index in 0..monkeys.len() {
+            monkeys[index].throw_count += monkeys[index].items.len() as u64;
 
             let m = {
-                let monkey = &mut monkeys[m];
+                let monkey = &mut monkeys[index];
                 let ret = Monkey {
                     items: monkey.items.clone(),
                     op: monkey.op,
@@ -139,8 +176,11 @@ fn run_monkeys(mut monkeys: std::vec::Vec<Monkey>, worry_reducer: WorryReducer,
             };
 
             for i in m.items {
-                let worry_level = worry_reducer.execute(m.op.execute(i));
-                let to_monkey = if (worry_level % m.div) == 0 { m.true_monkey } else { m.false_monkey };
+                let worry_level = match m.op.execute(i) {
+                    Some(v) => worry_reducer.execute(v),
+                    None => return Err(Box::from(format!("Worry level of monkey {} overflowed", index)))
+                };
+                let to_monkey = if worry_level.is_multiple_of(m.div) { m.true_monkey } else { m.false_monkey };
                 monkeys[to_monkey].items.push(worry_level);
             }
         }
@@ -148,19 +188,83 @@ fn run_monkeys(mut monkeys: std::vec::Vec<Monkey>, worry_reducer: WorryReducer,
 
     let mut throw_counts: std::vec::Vec<_> = monkeys.iter().map(|m| m.throw_count).collect();
     throw_counts.sort_by(|a, b| u64::cmp(b, a));
-    println!("Business: {}", throw_counts[0] * throw_counts[1]);
+    Ok(throw_counts.iter().take(2).product())
 }
 
-pub fn run_part1() -> Result<(), Box<dyn std::error::Error>> {
-    let monkeys = load_monkeys()?;
-    run_monkeys(monkeys, WorryReducer::Div3, 20);
-    Ok(())
+#[derive(Default)]
+pub struct Solution {
+    pub rounds: Option<usize>
 }
 
-pub fn run_part2() -> Result<(), Box<dyn std::error::Error>> {
-    let monkeys = load_monkeys()?;
-    let modulus = monkeys.iter().map(|m| m.div).product();
-    run_monkeys(monkeys, WorryReducer::Mod(modulus), 10000);
-    Ok(())
+impl Solver for Solution {
+    type Parsed = std::vec::Vec<Monkey>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>> {
+        Ok(parse(input)?)
+    }
+
+    fn part1(&self, monkeys: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        Answer::try_from(run_monkeys(monkeys.clone(), WorryReducer::Div3, self.rounds.unwrap_or(20))?)
+    }
+
+    fn part2(&self, monkeys: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let modulus = monkeys.iter().try_fold(1u64, |acc, m| acc.checked_mul(m.div)).ok_or("Product of the divisors overflowed")?;
+        Answer::try_from(run_monkeys(monkeys.clone(), WorryReducer::Mod(modulus), self.rounds.unwrap_or(10000))?)
+    }
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+        match (name, value.parse::<usize>()) {
+            ("rounds", Ok(rounds)) => self.rounds = Some(rounds),
+            ("rounds", Err(_)) => return Err(Box::from("rounds expects a number")),
+            _ => return Err(Box::from(format!("Unknown parameter {}", name)))
+        }
+        Ok(())
+    }
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MONKEY: &str =
+r#"Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 0
+    If false: throw to monkey 0"#;
+
+    #[test]
+    fn test_parse() {
+        let monkeys = parse(MONKEY).unwrap();
+        assert_eq!(monkeys.len(), 1);
+        assert_eq!(monkeys[0].items, vec![79, 98]);
+        assert_eq!(monkeys[0].div, 23);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse(&MONKEY.replace("* 19", "- 19")).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 24));
+
+        let err = parse(&MONKEY.replace("monkey 0\n", "monkey 1\n")).unwrap_err();
+        assert_eq!((err.line, err.column), (5, 30));
+        assert_eq!(err.expected, "a monkey below 1");
+
+        let err = parse(&MONKEY[..40]).unwrap_err();
+        assert_eq!(err.line, 3);
+
+        let err = parse(&format!("{}\nMonkey 1:", MONKEY)).unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (7, "a blank line before the next monkey"));
+
+        let line = "ÖÖ x";
+        let err = expect::<()>(Err(nom::Err::Error(nom::error::Error::new(&line[5..], nom::error::ErrorKind::Tag))), line, 1, "y").unwrap_err();
+        assert_eq!(err.column, 4);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let monkeys = parse(&MONKEY.replace("* 19", "* old")).unwrap();
+        assert!(run_monkeys(monkeys, WorryReducer::Div3, 20).is_err());
+    }
+}
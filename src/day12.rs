@@ -1,136 +1,122 @@
-use crate::common::read_file;
+use crate::common::{Grid, ParseError, Point};
+use crate::solver::{Answer, Solver};
 
-#[derive(Copy, Clone, PartialEq, Eq)]
-struct Cell {
-    row: usize,
-    col: usize
-}
-
-impl Cell {
-    fn new(row: usize, col: usize) -> Cell {
-        Cell { row, col }
-    }
-}
+pub type HeightMap = Grid<char>;
 
 struct QueuedCell {
-    cell: Cell,
+    cell: Point,
     steps: u32
 }
 
 impl QueuedCell {
-    fn new(cell: Cell, steps: u32) -> QueuedCell {
+    fn new(cell: Point, steps: u32) -> QueuedCell {
         QueuedCell { cell, steps }
     }
 }
 
-fn get_index_mask(cell: &Cell, width: usize) -> (usize, u8) {
-    let idx = (cell.row * width) + cell.col;
-    let mask = 1 << (idx % 8);
-
-    (idx, mask)
-}
-
-fn set_explored(cell: &Cell, width: usize, explored: &mut std::vec::Vec<u8>) {
-    let (idx, mask) = get_index_mask(cell, width);
-    explored[idx / 8] |= mask;
-}
-
-fn is_explored(cell: &Cell, width: usize, explored: &std::vec::Vec<u8>) -> bool {
-    let (idx, mask) = get_index_mask(cell, width);
-    (explored[idx / 8] & mask) != 0
+fn elevation(c: char) -> i8 {
+    match c {
+        'S' => b'a' as i8,
+        'E' => b'z' as i8,
+        c => c as i8
+    }
 }
 
-fn search(start: &Cell, map: &std::vec::Vec<std::vec::Vec<char>>, from_a: bool) {
-    let height = map.len() as i32;
-    let width = map[0].len() as i32;
-
+pub fn search(start: &Point, map: &HeightMap, from_a: bool) -> Option<u32> {
     let mut queue = std::collections::VecDeque::new();
-    let mut explored = vec![0u8; ((height * width) + 7 / 8) as usize];
+    let mut explored = Grid::new(map.width(), map.height(), false);
 
     queue.push_back(QueuedCell::new(*start, 0));
-    set_explored(start, width as usize, &mut explored);
+    explored[*start] = true;
 
     if from_a {
-        for (r, row) in map.iter().enumerate() {
-            for (c, &cell) in row.iter().enumerate() {
-                if cell == 'a' {
-                    let cell = Cell::new(r, c);
-                    queue.push_back(QueuedCell::new(cell, 0));
-                    set_explored(&cell, width as usize, &mut explored);
-                }
+        for p in map.points() {
+            if map[p] == 'a' {
+                queue.push_back(QueuedCell::new(p, 0));
+                explored[p] = true;
             }
         }
     }
 
-    while !queue.is_empty() {
-        let next = queue.pop_front().unwrap();
-
-        if map[next.cell.row][next.cell.col] == 'E' {
-            println!("Found: {}", next.steps);
-            return;
+    while let Some(next) = queue.pop_front() {
+        let this_cell = map[next.cell];
+        if this_cell == 'E' {
+            crate::trace!("Found E at ({}, {}) after {} steps", next.cell.x, next.cell.y, next.steps);
+            return Some(next.steps);
         }
 
-        for (row, col) in [(0,1), (0,-1), (1,0), (-1,0)] {
-            let nr = next.cell.row as i32 + row;
-            let nc = next.cell.col as i32 + col;
-
-            if nr < 0 || nc < 0 || nr >= height || nc >= width {
+        for n in next.cell.neighbours4().filter(|&n| map.contains(n)) {
+            if explored[n] {
                 continue;
             }
 
-            if is_explored(&Cell::new(nr as usize, nc as usize), width as usize, &explored) {
-                continue;
-            }
-
-            let mut this_cell = map[next.cell.row][next.cell.col];
-            if this_cell == 'S' {
-                this_cell = 'a';
-            }
-
-            let mut next_cell = map[nr as usize][nc as usize];
-            if next_cell == 'E' {
-                next_cell = 'z';
-            }
-
-            if (next_cell as i8 - this_cell as i8) > 1 {
+            if elevation(map[n]) - elevation(this_cell) > 1 {
                 continue;
             }
 
-            let steps = next.steps + 1;
-
-            let cell = Cell::new(nr as usize, nc as usize);
-            queue.push_back(QueuedCell::new(cell, steps));
-            set_explored(&cell, width as usize, &mut explored);
+            queue.push_back(QueuedCell::new(n, next.steps + 1));
+            explored[n] = true;
         }
     }
+
+    None
 }
 
-fn find_start(map: &std::vec::Vec<std::vec::Vec<char>>) -> Result<Cell, Box<dyn std::error::Error>> {
-    for (r, row) in map.iter().enumerate() {
-        for (c, &cell) in row.iter().enumerate() {
-            if cell == 'S' {
-                return Ok(Cell::new(r, c));
-            }
-        }
+pub fn find_start(map: &HeightMap) -> Result<Point, Box<dyn std::error::Error>> {
+    match map.points().find(|&p| map[p] == 'S') {
+        Some(p) => Ok(p),
+        None => Err(Box::from("Did not find start"))
     }
+}
 
-    Err(Box::from("Did not find start"))
+pub fn parse_map(input: &str) -> Result<HeightMap, ParseError> {
+    Grid::parse(12, input, "a height a-z, S or E", |c| {
+        if c.is_ascii_lowercase() || c == 'S' || c == 'E' { Some(c) } else { None }
+    })
 }
 
-pub fn run_part1() -> Result<(), Box<dyn std::error::Error>> {
-    let map: std::vec::Vec<_> = read_file("day12.txt")?.iter().map(|line| line.chars().collect::<std::vec::Vec<char>>()).collect();
-    let start = find_start(&map)?;
+#[derive(Default)]
+pub struct Solution;
 
-    search(&start, &map, false);
-    Ok(())
-}
+impl Solver for Solution {
+    type Parsed = HeightMap;
 
-pub fn run_part2() -> Result<(), Box<dyn std::error::Error>> {
-    let map: std::vec::Vec<_> = read_file("day12.txt")?.iter().map(|line| line.chars().collect::<std::vec::Vec<char>>()).collect();
-    let start = find_start(&map)?;
+    fn show(&self, map: &Self::Parsed) -> String {
+        map.render(|&c| c).join("\n")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>> {
+        Ok(parse_map(input)?)
+    }
 
-    search(&start, &map, true);
-    Ok(())
+    fn part1(&self, map: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let start = find_start(map)?;
+
+        match search(&start, map, false) {
+            None => Err(Box::from("No path to E")),
+            Some(steps) => Ok(Answer::from(steps))
+        }
+    }
+
+    fn part2(&self, map: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let start = find_start(map)?;
+
+        match search(&start, map, true) {
+            None => Err(Box::from("No path to E")),
+            Some(steps) => Ok(Answer::from(steps))
+        }
+    }
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
 
+    #[test]
+    fn test_parse_map() {
+        let map = parse_map("Sab\nzyE\n").unwrap();
+        assert_eq!(map.render(|&c| c), vec!["Sab".to_owned(), "zyE".to_owned()]);
+        assert_eq!(parse_map("Sab\nz1E").unwrap_err(), ParseError::new(12, 2, 2, "z1E", "a height a-z, S or E"));
+        assert_eq!(parse_map("Sab\nzE").unwrap_err(), ParseError::new(12, 2, 1, "zE", "a row of width 3"));
+    }
+}
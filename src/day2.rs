@@ -1,14 +1,15 @@
-use std::fs;
+use crate::common::ParseError;
+use crate::solver::{Answer, Solver};
 
 #[derive(Eq, PartialEq, Debug)]
-enum GameResult {
+pub enum GameResult {
     Win,
     Lose,
     Draw
 }
 
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
-enum Hand {
+pub enum Hand {
     Rock,
     Paper,
     Scissors
@@ -24,12 +25,12 @@ impl Hand {
         }
     }
 
-    fn get_advised_hand(&self, input: &str) -> Option<Hand> {
+    pub fn get_advised_hand(&self, input: &str) -> Option<Hand> {
         let desired_result = match input {
             "X" => GameResult::Lose,
             "Y" => GameResult::Draw,
             "Z" => GameResult::Win,
-            _ => panic!("Bad advised input")
+            _ => return None
         };
 
         Some(
@@ -53,15 +54,15 @@ impl Hand {
         )
     }
 
-    fn score(&self) -> i32 {
+    pub fn score(&self, rules: &ScoringRules) -> i32 {
         match self {
-            Hand::Rock => 1,
-            Hand::Paper => 2,
-            Hand::Scissors => 3
+            Hand::Rock => rules.rock,
+            Hand::Paper => rules.paper,
+            Hand::Scissors => rules.scissors
         }
     }
 
-    fn get_result(&self, opp: Hand) -> GameResult {
+    pub fn get_result(&self, opp: Hand) -> GameResult {
         if *self == opp {
             return GameResult::Draw
         }
@@ -82,106 +83,159 @@ impl Hand {
 }
 
 #[derive(PartialEq, Debug, Copy, Clone)]
-struct Round {
-    opp: Hand,
-    me: Hand
+pub struct Round {
+    pub opp: Hand,
+    pub me: Hand
 }
 
 impl Round {
-    fn new(opp: Hand, me: Hand) -> Round {
+    pub fn new(opp: Hand, me: Hand) -> Round {
         Round {
             opp,
             me
         }
     }
 
-    fn calc_result(&self) -> i32 {
+    pub fn calc_result(&self, rules: &ScoringRules) -> i64 {
         let res = self.me.get_result(self.opp);
-        let hand_score = self.me.score();
+        let hand_score = self.me.score(rules);
 
-        hand_score + if res == GameResult::Draw {
-            3
+        let result_score = if res == GameResult::Draw {
+            rules.draw
         }
         else if res == GameResult::Win {
-            6
+            rules.win
         }
         else { // Lose
-            0
-        }
+            rules.lose
+        };
+
+        hand_score as i64 + result_score as i64
     }
 }
 
-pub fn run_part1() -> Result<(), Box<dyn std::error::Error>> {
-    let contents = fs::read_to_string("day2.txt")?;
-    let total_score = calc_total_score(&contents);
-    println!("Total score: {}", total_score); 
-    Ok(())
+// Points for the hand played and for the outcome of a round. The defaults are the puzzle's
+// rules; each field can be changed by name, e.g. set win 10.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct ScoringRules {
+    pub rock: i32,
+    pub paper: i32,
+    pub scissors: i32,
+    pub lose: i32,
+    pub draw: i32,
+    pub win: i32
 }
 
-pub fn run_part2() -> Result<(), Box<dyn std::error::Error>> {
-    let contents = fs::read_to_string("day2.txt")?;
-    let total_score = calc_total_score_advised(&contents);
-    println!("Total score: {}", total_score); 
-    Ok(())
+impl Default for ScoringRules {
+    fn default() -> ScoringRules {
+        ScoringRules { rock: 1, paper: 2, scissors: 3, lose: 0, draw: 3, win: 6 }
+    }
 }
 
-fn calc_total_score(contents: &str) -> i32 {
-    let rounds = parse_input(&contents);
+impl ScoringRules {
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let field = match name {
+            "rock" => &mut self.rock,
+            "paper" => &mut self.paper,
+            "scissors" => &mut self.scissors,
+            "lose" => &mut self.lose,
+            "draw" => &mut self.draw,
+            "win" => &mut self.win,
+            _ => return Err(Box::from(format!("Unknown parameter {}", name)))
+        };
 
-    rounds.iter().map(|r| r.calc_result()).sum()
+        match value.parse::<i32>() {
+            Ok(points) => *field = points,
+            Err(_) => return Err(Box::from(format!("{} expects a number of points", name)))
+        }
+        Ok(())
+    }
 }
 
-fn calc_total_score_advised(contents: &str) -> i32 {
-    let rounds = parse_input_advised(&contents);
+#[derive(Debug)]
+pub struct Guide {
+    pub rounds: std::vec::Vec<Round>,
+    pub advised_rounds: std::vec::Vec<Round>
+}
 
-    rounds.iter().map(|r| r.calc_result()).sum()
+#[derive(Default)]
+pub struct Solution {
+    pub rules: ScoringRules
 }
-fn parse_input(input: &str) -> std::vec::Vec<Round> {
-    let rounds = input.split("\n");
-    let rounds: std::vec::Vec<Round> = rounds.filter(|line| !line.is_empty()).map(|line| {
-        let mut hands = line.split(" ");
-
-        let opp = match hands.next() {
-            Some(x) => x.trim(),
-            None => panic!("Expected first hand")
-        };
 
-        let me = match hands.next() {
-            Some(x) => x.trim(),
-            None => panic!("Expected second hand")
-        };
+impl Solver for Solution {
+    type Parsed = Guide;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>> {
+        Ok(Guide {
+            rounds: parse_input(input)?,
+            advised_rounds: parse_input_advised(input)?
+        })
+    }
+
+    fn part1(&self, guide: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let total_score = calc_total_score(&guide.rounds, &self.rules);
+        Ok(Answer::Int(total_score))
+    }
 
-        Round::new(
-            Hand::from_str(opp).expect("Valid hand opp"),
-            Hand::from_str(me).expect("Valid hand me"))
-    }).collect();
+    fn part2(&self, guide: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let total_score = calc_total_score(&guide.advised_rounds, &self.rules);
+        Ok(Answer::Int(total_score))
+    }
 
-    rounds
+    fn set(&mut self, name: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.rules.set(name, value)
+    }
 }
 
-fn parse_input_advised(input: &str) -> std::vec::Vec<Round> {
-    let rounds = input.split("\n");
-    let rounds: std::vec::Vec<Round> = rounds.filter(|line| !line.is_empty()).map(|line| {
-        println!("{}", line);
-        let mut hands = line.split(" ");
+pub fn calc_total_score(rounds: &[Round], rules: &ScoringRules) -> i64 {
+    rounds.iter().map(|r| r.calc_result(rules)).sum()
+}
 
-        let opp = match hands.next() {
-            Some(x) => x.trim(),
-            None => panic!("Expected first hand")
-        };
+fn split_hands(line: &str, line_no: usize) -> Result<(Hand, &str), ParseError> {
+    let mut hands = line.split(' ');
 
-        let me = match hands.next() {
-            Some(x) => x.trim(),
-            None => panic!("Expected second hand")
-        };
+    let opp = match hands.next() {
+        Some(x) => x.trim(),
+        None => return Err(ParseError::new(2, line_no, 1, line, "first hand"))
+    };
 
-        let opp = Hand::from_str(opp).expect("Valid hand opp");
-        Round::new(
-            opp,
-            opp.get_advised_hand(me).expect("Valid hand me"))
-    }).collect();
+    let me = match hands.next() {
+        Some(x) => x.trim(),
+        None => return Err(ParseError::new(2, line_no, line.len() + 1, line, "second hand"))
+    };
+
+    match Hand::from_str(opp) {
+        None => Err(ParseError::at(2, line_no, line, opp, "A, B or C")),
+        Some(opp) => Ok((opp, me))
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<std::vec::Vec<Round>, ParseError> {
+    let rounds = input.split('\n').enumerate();
+    rounds.filter(|(_, line)| !line.is_empty()).map(|(i, line)| {
+        let (opp, me) = split_hands(line, i + 1)?;
 
-    rounds
+        match Hand::from_str(me) {
+            None => Err(ParseError::at(2, i + 1, line, me, "X, Y or Z")),
+            Some(me) => Ok(Round::new(opp, me))
+        }
+    }).collect()
+}
+
+pub fn parse_input_advised(input: &str) -> Result<std::vec::Vec<Round>, ParseError> {
+    let rounds = input.split('\n').enumerate();
+    rounds.filter(|(_, line)| !line.is_empty()).map(|(i, line)| {
+        let (opp, me) = split_hands(line, i + 1)?;
+
+        match opp.get_advised_hand(me) {
+            None => Err(ParseError::at(2, i + 1, line, me, "X, Y or Z")),
+            Some(hand) => {
+                crate::trace!("line {}: {:?} with advice {} -> {:?}", i + 1, opp, me, hand);
+                Ok(Round::new(opp, hand))
+            }
+        }
+    }).collect()
 }
 
 #[cfg(test)]
@@ -200,7 +254,7 @@ B Z
 C X
 C Y
 C Z"#;
-        let rounds = parse_input(input);
+        let rounds = parse_input(input).unwrap();
         assert_eq!(rounds.len(), 9);
         assert_eq!(rounds[0], Round::new(Hand::Rock, Hand::Rock));
         assert_eq!(rounds[1], Round::new(Hand::Rock, Hand::Paper));
@@ -232,17 +286,18 @@ C Z"#;
 
     #[test]
     fn test_round() {
-        assert_eq!(Round::new(Hand::Rock, Hand::Rock).calc_result(), 3 + 1);
-        assert_eq!(Round::new(Hand::Paper, Hand::Paper).calc_result(), 3 + 2);
-        assert_eq!(Round::new(Hand::Scissors, Hand::Scissors).calc_result(), 3 + 3);
-
-        assert_eq!(Round::new(Hand::Paper, Hand::Rock).calc_result(), 1);
-        assert_eq!(Round::new(Hand::Scissors, Hand::Paper).calc_result(), 2);
-        assert_eq!(Round::new(Hand::Rock, Hand::Scissors).calc_result(), 3);
-
-        assert_eq!(Round::new(Hand::Scissors, Hand::Rock).calc_result(), 6 + 1);
-        assert_eq!(Round::new(Hand::Rock, Hand::Paper).calc_result(), 6 + 2);
-        assert_eq!(Round::new(Hand::Paper, Hand::Scissors).calc_result(), 6 + 3);
+        let rules = ScoringRules::default();
+        assert_eq!(Round::new(Hand::Rock, Hand::Rock).calc_result(&rules), 3 + 1);
+        assert_eq!(Round::new(Hand::Paper, Hand::Paper).calc_result(&rules), 3 + 2);
+        assert_eq!(Round::new(Hand::Scissors, Hand::Scissors).calc_result(&rules), 3 + 3);
+
+        assert_eq!(Round::new(Hand::Paper, Hand::Rock).calc_result(&rules), 1);
+        assert_eq!(Round::new(Hand::Scissors, Hand::Paper).calc_result(&rules), 2);
+        assert_eq!(Round::new(Hand::Rock, Hand::Scissors).calc_result(&rules), 3);
+
+        assert_eq!(Round::new(Hand::Scissors, Hand::Rock).calc_result(&rules), 6 + 1);
+        assert_eq!(Round::new(Hand::Rock, Hand::Paper).calc_result(&rules), 6 + 2);
+        assert_eq!(Round::new(Hand::Paper, Hand::Scissors).calc_result(&rules), 6 + 3);
     }
 
     #[test]
@@ -252,10 +307,26 @@ r#"A Y
 B X
 C Z"#;
 
-        let total_score = calc_total_score(&input);
+        let total_score = calc_total_score(&parse_input(input).unwrap(), &ScoringRules::default());
         assert_eq!(total_score, 15);
     }
 
+    #[test]
+    fn test_scoring_rules() {
+        let mut solution = Solution::default();
+        solution.set("win", "10").unwrap();
+        solution.set("lose", "-2").unwrap();
+        solution.set("rock", "5").unwrap();
+        solution.set("scissors", "0").unwrap();
+        assert!(solution.set("draw", "lots").is_err());
+        assert!(solution.set("lizard", "5").is_err());
+        assert_eq!(solution.rules, ScoringRules { rock: 5, paper: 2, scissors: 0, lose: -2, draw: 3, win: 10 });
+
+        let guide = solution.parse("A Y\nB X\nC Z\n").unwrap();
+        assert_eq!(solution.part1(&guide).unwrap(), Answer::Int(18));
+        assert_eq!(solution.part2(&guide).unwrap(), Answer::Int(26));
+    }
+
     #[test]
     fn test_hand_advised() {
         assert_eq!(Hand::Rock.get_advised_hand("X").unwrap(), Hand::Scissors);
@@ -283,7 +354,7 @@ B Z
 C X
 C Y
 C Z"#;
-        let rounds = parse_input_advised(input);
+        let rounds = parse_input_advised(input).unwrap();
         assert_eq!(rounds.len(), 9);
         assert_eq!(rounds[0], Round::new(Hand::Rock, Hand::Scissors));
         assert_eq!(rounds[1], Round::new(Hand::Rock, Hand::Rock));
@@ -297,4 +368,11 @@ C Z"#;
         assert_eq!(rounds[7], Round::new(Hand::Scissors, Hand::Scissors));
         assert_eq!(rounds[8], Round::new(Hand::Scissors, Hand::Rock));
     }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_input("A X\nA Q").unwrap_err(), ParseError::new(2, 2, 3, "A Q", "X, Y or Z"));
+        assert_eq!(parse_input("A X\n\nD Y").unwrap_err(), ParseError::new(2, 3, 1, "D Y", "A, B or C"));
+        assert_eq!(parse_input_advised("B").unwrap_err(), ParseError::new(2, 1, 2, "B", "second hand"));
+    }
 }
@@ -1,38 +1,56 @@
-use std::fs;
-
-pub fn run_part1() -> Result<(), Box<dyn std::error::Error>> {
-    let contents = fs::read_to_string("day3.txt")?;
-    let compartments = parse_input(&contents)?;
-    let score: u32 = compartments
-    .iter()
-    .map(|c| {
-        let common = find_common(c.0, c.1);
-        score_char(common)
-    })
-    .sum();
-
-    println!("Score: {}", score);
-
-    Ok(())
+use crate::common::ParseError;
+use crate::solver::{Answer, Solver};
+
+#[derive(Debug)]
+pub struct Rucksacks {
+    pub compartments: std::vec::Vec<(String, String)>,
+    pub groups: std::vec::Vec<(String, String, String)>
 }
 
-pub fn run_part2() -> Result<(), Box<dyn std::error::Error>> {
-    let contents = fs::read_to_string("day3.txt")?;
-    let rucksacks = parse_input3(&contents);
+#[derive(Default)]
+pub struct Solution;
+
+impl Solver for Solution {
+    type Parsed = Rucksacks;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>> {
+        let compartments = parse_input(input)?
+            .iter()
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .collect();
 
-    let total: u32 = rucksacks
-        .iter()
-        .map(|x| {
-            let c = find_common3(&x.0, &x.1, &&x.2);
-            score_char(c)
+        Ok(Rucksacks {
+            compartments,
+            groups: parse_input3(input)?
         })
-        .sum();
+    }
+
+    fn part1(&self, rucksacks: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let mut score = 0;
+        for (i, c) in rucksacks.compartments.iter().enumerate() {
+            match find_common(&c.0, &c.1) {
+                None => return Err(Box::from(format!("Rucksack {} has no item in both compartments", i + 1))),
+                Some(common) => score += score_char(common)
+            }
+        }
 
-    println!("Sum: {}", total);
-    Ok(())
+        Ok(Answer::from(score))
+    }
+
+    fn part2(&self, rucksacks: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let mut total = 0;
+        for (i, x) in rucksacks.groups.iter().enumerate() {
+            match find_common3(&x.0, &x.1, &x.2) {
+                None => return Err(Box::from(format!("Group {} has no badge item in common", i + 1))),
+                Some(c) => total += score_char(c)
+            }
+        }
+
+        Ok(Answer::from(total))
+    }
 }
 
-fn find_common3(a: &str, b: &str, c: &str) -> char {
+pub fn find_common3(a: &str, b: &str, c: &str) -> Option<char> {
     let mut a: std::vec::Vec<char> = a.chars().collect();
     let mut b: std::vec::Vec<char> = b.chars().collect();
     let mut c: std::vec::Vec<char> = c.chars().collect();
@@ -51,61 +69,60 @@ fn find_common3(a: &str, b: &str, c: &str) -> char {
     let len = a.len();
     for i in 2..len {
         if a[i] == a[i-2] && a[i] == a[i-1] {
-            return a[i]
+            return Some(a[i])
         }
     }
 
-    panic!("No dups");
+    None
+}
+
+fn check_items(line: &str, line_no: usize) -> Result<(), ParseError> {
+    match line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+        None => Ok(()),
+        Some((i, _)) => Err(ParseError::at(3, line_no, line, &line[i..], "an item letter a-z or A-Z"))
+    }
 }
 
-fn parse_input(input: &str) -> Result<std::vec::Vec<(&str, &str)>, Box<dyn std::error::Error>> {
-    let rucksacks = input.split("\n");
+pub fn parse_input(input: &str) -> Result<std::vec::Vec<(&str, &str)>, ParseError> {
+    let rucksacks = input.split('\n').enumerate();
 
-    let compartments = rucksacks
-    .filter(|line| !line.is_empty())
-    .map(|line| {
+    rucksacks
+    .filter(|(_, line)| !line.is_empty())
+    .map(|(i, line)| {
+        check_items(line, i + 1)?;
         if (line.len() % 2) != 0 {
-            panic!("Input length is not even")
+            return Err(ParseError::new(3, i + 1, line.len() + 1, line, "an even number of items"))
         }
 
         let mid = line.len() / 2;
-        (&line[..mid], &line[mid..])
-    }).collect::<>();
-
-    Ok(compartments)
+        Ok((&line[..mid], &line[mid..]))
+    }).collect()
 }
 
-fn parse_input3(input: &str) -> std::vec::Vec<(String, String, String)> {
-    let rucksacks: std::vec::Vec<&str> = input.split("\n").filter(|x| !x.is_empty()).collect();
+pub fn parse_input3(input: &str) -> Result<std::vec::Vec<(String, String, String)>, ParseError> {
+    let rucksacks: std::vec::Vec<(usize, &str)> = input.split('\n').enumerate().filter(|(_, x)| !x.is_empty()).collect();
+
+    for (i, line) in &rucksacks {
+        check_items(line, i + 1)?;
+    }
 
     let iter = rucksacks.chunks_exact(3);
-    if iter.remainder().len() != 0 {
-        panic!("Did not expect any remainder");
+    if let Some((i, line)) = iter.remainder().first() {
+        return Err(ParseError::new(3, i + 1, 1, line, "groups of three rucksacks"));
     }
 
-    iter.map(|x| (x[0].to_string(), x[1].to_string(), x[2].to_string())).collect()
+    Ok(iter.map(|x| (x[0].1.to_string(), x[1].1.to_string(), x[2].1.to_string())).collect())
 }
 
-fn find_common(a: &str, b: &str) -> char {
-    let res: std::vec::Vec<char> = a.chars().filter(|c| {
-        match b.find(*c) {
-            None => false,
-            Some(_) => true
-        }
-    }).collect();
-
-    if res.len() == 0 {
-        panic!("Expected none empty")
-    }
-
-    return res[0]
+pub fn find_common(a: &str, b: &str) -> Option<char> {
+    a.chars().find(|c| b.contains(*c))
 }
 
-fn score_char(c: char) -> u32 {
-    if 'a' <= c && c <= 'z' {
+pub fn score_char(c: char) -> u32 {
+    if c.is_ascii_lowercase() {
         return c as u32 - 'a' as u32 + 1
     }
-    else if 'A' <= c && c <= 'Z' {
+    else if c.is_ascii_uppercase() {
         return c as u32 - 'A' as u32 + 27
     }
 
@@ -136,9 +153,10 @@ fDfNHHjVFNvvrvVBJJdS"#;
 
     #[test]
     fn test_find_common() {
-        assert_eq!(find_common("CjhshBJCSr", "TTsLwqwqwb"), 's');
-        assert_eq!(find_common("GtmnFHlDfcpH", "bLZjtTTRLWwb"), 't');
-        assert_eq!(find_common("fDfNHHjVFN", "vvrvVBJJdS"), 'V');
+        assert_eq!(find_common("CjhshBJCSr", "TTsLwqwqwb"), Some('s'));
+        assert_eq!(find_common("GtmnFHlDfcpH", "bLZjtTTRLWwb"), Some('t'));
+        assert_eq!(find_common("fDfNHHjVFN", "vvrvVBJJdS"), Some('V'));
+        assert_eq!(find_common("abc", "def"), None);
     }
 
     #[test]
@@ -162,7 +180,7 @@ bBBGBfmGvBTnGtGJBtGpcJbZrrddjqrZhDldwdcqrjrjDr
 HWPSQMsPHFsMWPVVMVSHCwDCDwwZZvwjwQZZwjdd
 vVHPgHHFRLfpfJTLLtJL"#;
 
-        let r = parse_input3(input);
+        let r = parse_input3(input).unwrap();
         assert_eq!(r.len(), 3);
         assert_eq!(r[0], ("CjhshBJCSrTTsLwqwqwb".to_owned(), "GtmnFHlDfcpHbLZjtTTRLWwb".to_owned(), "fDfNHHjVFNvvrvVBJJdS".to_owned()));
         assert_eq!(r[1], ("PPWvWQjPhrPQwlMWJJdMDGbJTdCJ".to_owned(), "rsqsStgNNggBNBZHSrJGdJdCFRRZCFbGbTdJ".to_owned(), "qgBqqHzzggBpzSnBNqNSSSgcfhrVlVmwPljQVLVwVvQmmzVl".to_owned()));
@@ -171,6 +189,14 @@ vVHPgHHFRLfpfJTLLtJL"#;
 
     #[test]
     fn test_find_common3() {
-        assert_eq!(find_common3("abcdef", "ghijfkl", "mnopfqrd"), 'f');
+        assert_eq!(find_common3("abcdef", "ghijfkl", "mnopfqrd"), Some('f'));
+        assert_eq!(find_common3("abc", "bcd", "def"), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_input("abcd\nabc").unwrap_err(), ParseError::new(3, 2, 4, "abc", "an even number of items"));
+        assert_eq!(parse_input("ab1d").unwrap_err(), ParseError::new(3, 1, 3, "ab1d", "an item letter a-z or A-Z"));
+        assert_eq!(parse_input3("ab\ncd\nef\ngh").unwrap_err(), ParseError::new(3, 4, 1, "gh", "groups of three rucksacks"));
     }
 }
@@ -1,77 +1,97 @@
-use crate::common::read_file;
+use crate::common::{lines, ParseError};
+use crate::solver::{Answer, Solver};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Pair {
-    min: u32,
-    max: u32
+pub struct Pair {
+    pub min: u32,
+    pub max: u32
 }
 
 impl Pair {
-    fn new(min: u32, max: u32) -> Pair {
+    pub fn new(min: u32, max: u32) -> Pair {
         Pair {
             min,
             max
         }
     }
 
-    fn fully_contains(&self, other: &Pair) -> bool {
+    pub fn fully_contains(&self, other: &Pair) -> bool {
         self.min <= other.min && other.max <= self.max
     }
 
-    fn does_overlap(&self, other: &Pair) -> bool {
+    pub fn does_overlap(&self, other: &Pair) -> bool {
         other.min <= self.max && self.min <= other.max
     }
 }
 
-pub fn run_part1() -> Result<(), Box<dyn std::error::Error>> {
-    let lines = read_file("day4.txt")?;
-    let contain_pair_count: u32 = lines
-        .iter()
-        .map(|l| {
-            let (lhs, rhs) = parse_line(l);
-            if lhs.fully_contains(&rhs) || rhs.fully_contains(&lhs) {
-                1
-            }
-            else {
-                0
-            }
-        })
-        .sum();
-
-    println!("Pair sum: {}", contain_pair_count);
-    Ok(())
+#[derive(Default)]
+pub struct Solution;
+
+impl Solver for Solution {
+    type Parsed = std::vec::Vec<(Pair, Pair)>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>> {
+        let pairs: Result<Self::Parsed, ParseError> = lines(input)
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| !l.is_empty())
+            .map(|(i, l)| parse_line(l, i + 1))
+            .collect();
+
+        Ok(pairs?)
+    }
+
+    fn part1(&self, pairs: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let contain_pair_count: u32 = pairs
+            .iter()
+            .map(|(lhs, rhs)| {
+                if lhs.fully_contains(rhs) || rhs.fully_contains(lhs) {
+                    1
+                }
+                else {
+                    0
+                }
+            })
+            .sum();
+
+        Ok(Answer::from(contain_pair_count))
+    }
+
+    fn part2(&self, pairs: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let contain_pair_count: u32 = pairs
+            .iter()
+            .map(|(lhs, rhs)| {
+                if lhs.does_overlap(rhs) {
+                    1
+                }
+                else {
+                    0
+                }
+            })
+            .sum();
+
+        Ok(Answer::from(contain_pair_count))
+    }
 }
 
-pub fn run_part2() -> Result<(), Box<dyn std::error::Error>> {
-    let lines = read_file("day4.txt")?;
-    let contain_pair_count: u32 = lines
-        .iter()
-        .map(|l| {
-            let (lhs, rhs) = parse_line(l);
-            if lhs.does_overlap(&rhs) {
-                1
-            }
-            else {
-                0
-            }
-        })
-        .sum();
-
-    println!("Pair sum: {}", contain_pair_count);
-    Ok(())
+fn parse_section(line: &str, section: &str, line_no: usize) -> Result<u32, ParseError> {
+    match section.parse() {
+        Ok(v) => Ok(v),
+        Err(_) => Err(ParseError::at(4, line_no, line, section, "a section number"))
+    }
 }
 
-fn parse_pair(line: &str) -> Pair {
-    match line.split_once('-') {
-        None => panic!("Failed to split pair: {}", line),
-        Some((lhs, rhs)) => Pair::new(lhs.parse().unwrap(), rhs.parse().unwrap())
+fn parse_pair(line: &str, pair: &str, line_no: usize) -> Result<Pair, ParseError> {
+    match pair.split_once('-') {
+        None => Err(ParseError::at(4, line_no, line, &pair[pair.len()..], "'-'")),
+        Some((lhs, rhs)) => Ok(Pair::new(parse_section(line, lhs, line_no)?, parse_section(line, rhs, line_no)?))
     }
 }
 
-fn parse_line(line: &str) -> (Pair, Pair) {
+pub fn parse_line(line: &str, line_no: usize) -> Result<(Pair, Pair), ParseError> {
     match line.split_once(',') {
-        None => panic!("Failed to split line {}", line),
-        Some((lhs, rhs)) => (parse_pair(lhs), parse_pair(rhs))
+        None => Err(ParseError::at(4, line_no, line, &line[line.len()..], "','")),
+        Some((lhs, rhs)) => Ok((parse_pair(line, lhs, line_no)?, parse_pair(line, rhs, line_no)?))
     }
 }
 
@@ -81,38 +101,45 @@ mod test {
 
     #[test]
     fn test_parse_pair() {
-        assert_eq!(parse_pair("13-53"), Pair::new(13, 53));
-        assert_eq!(parse_pair("17-82"), Pair::new(17, 82));
-        assert_eq!(parse_pair("32-32"), Pair::new(32, 32));
-        assert_eq!(parse_pair("32-42"), Pair::new(32, 42));
+        assert_eq!(parse_pair("13-53", "13-53", 1).unwrap(), Pair::new(13, 53));
+        assert_eq!(parse_pair("17-82", "17-82", 1).unwrap(), Pair::new(17, 82));
+        assert_eq!(parse_pair("32-32", "32-32", 1).unwrap(), Pair::new(32, 32));
+        assert_eq!(parse_pair("32-42", "32-42", 1).unwrap(), Pair::new(32, 42));
     }
 
     #[test]
     fn test_parse_line() {
-        assert_eq!(parse_line("60-71,59-70"), (Pair::new(60, 71), Pair::new(59, 70)));
-        assert_eq!(parse_line("91-92,4-90"), (Pair::new(91, 92), Pair::new(4, 90)));
-        assert_eq!(parse_line("90-90,1-90"), (Pair::new(90, 90), Pair::new(1, 90)));
+        assert_eq!(parse_line("60-71,59-70", 1).unwrap(), (Pair::new(60, 71), Pair::new(59, 70)));
+        assert_eq!(parse_line("91-92,4-90", 1).unwrap(), (Pair::new(91, 92), Pair::new(4, 90)));
+        assert_eq!(parse_line("90-90,1-90", 1).unwrap(), (Pair::new(90, 90), Pair::new(1, 90)));
     }
 
     #[test]
     fn test_fully_contains() {
-        assert_eq!(Pair::new(32, 42).fully_contains(&Pair::new(32, 32)), true);
-        assert_eq!(Pair::new(60, 71).fully_contains(&Pair::new(59, 70)), false);
+        assert!(Pair::new(32, 42).fully_contains(&Pair::new(32, 32)));
+        assert!(!Pair::new(60, 71).fully_contains(&Pair::new(59, 70)));
     }
 
     #[test]
     fn test_does_overlap() {
-        assert_eq!(Pair::new(2,4).does_overlap(&Pair::new(6,8)), false);
-        assert_eq!(Pair::new(2,3).does_overlap(&Pair::new(4,5)), false);
-
-        assert_eq!(Pair::new(5,7).does_overlap(&Pair::new(7,9)), true);
-        assert_eq!(Pair::new(2,8).does_overlap(&Pair::new(3,7)), true);
-        assert_eq!(Pair::new(6,6).does_overlap(&Pair::new(4,6)), true);
-        assert_eq!(Pair::new(2,6).does_overlap(&Pair::new(4,8)), true);
-
-        assert_eq!(Pair::new(7,9).does_overlap(&Pair::new(5,7)), true);
-        assert_eq!(Pair::new(3,7).does_overlap(&Pair::new(2,8)), true);
-        assert_eq!(Pair::new(4,6).does_overlap(&Pair::new(6,6)), true);
-        assert_eq!(Pair::new(4,8).does_overlap(&Pair::new(2,6)), true);
+        assert!(!Pair::new(2,4).does_overlap(&Pair::new(6,8)));
+        assert!(!Pair::new(2,3).does_overlap(&Pair::new(4,5)));
+
+        assert!(Pair::new(5,7).does_overlap(&Pair::new(7,9)));
+        assert!(Pair::new(2,8).does_overlap(&Pair::new(3,7)));
+        assert!(Pair::new(6,6).does_overlap(&Pair::new(4,6)));
+        assert!(Pair::new(2,6).does_overlap(&Pair::new(4,8)));
+
+        assert!(Pair::new(7,9).does_overlap(&Pair::new(5,7)));
+        assert!(Pair::new(3,7).does_overlap(&Pair::new(2,8)));
+        assert!(Pair::new(4,6).does_overlap(&Pair::new(6,6)));
+        assert!(Pair::new(4,8).does_overlap(&Pair::new(2,6)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_line("60-71;59-70", 3).unwrap_err(), ParseError::new(4, 3, 12, "60-71;59-70", "','"));
+        assert_eq!(parse_line("60-71,5970", 3).unwrap_err(), ParseError::new(4, 3, 11, "60-71,5970", "'-'"));
+        assert_eq!(parse_line("60-71,59-x", 3).unwrap_err(), ParseError::new(4, 3, 10, "60-71,59-x", "a section number"));
     }
 }
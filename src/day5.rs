@@ -1,50 +1,60 @@
-use crate::common::read_file2;
+use crate::common::{lines, ParseError};
+use crate::solver::{Answer, Solver};
 use regex::Regex;
 
-struct Move {
-    from: usize,
-    to: usize,
-    count: usize
+#[derive(Debug)]
+pub struct Move {
+    pub from: usize,
+    pub to: usize,
+    pub count: usize
 }
 
-type Stacks = std::vec::Vec<std::vec::Vec<char>>;
-type Moves = std::vec::Vec<Move>;
+pub type Stacks = std::vec::Vec<std::vec::Vec<char>>;
+pub type Moves = std::vec::Vec<Move>;
 
-fn parse_input(lines: &std::vec::Vec<String>) -> Result<(Stacks, Moves), Box<dyn std::error::Error>> {
+pub fn parse_input(lines: &[&str]) -> Result<(Stacks, Moves), ParseError> {
     let stacks_text = match lines.iter().position(|s| s.starts_with(" 1")) {
             Some(i) => &lines[..i],
-            None => return Err(Box::from("Invalid input")),
+            None => return Err(ParseError::new(5, 1, 1, lines.first().copied().unwrap_or(""), "a ' 1   2 ...' stack number row")),
         };
 
-    let stack_count = (stacks_text[0].len() / 4) + 1;
+    let stack_count = lines[stacks_text.len()].split_whitespace().count();
     let mut stacks_output: Stacks = vec![];
     stacks_output.resize_with(stack_count, Default::default);
 
     for row in stacks_text {
-        for i in 0..stack_count {
+        for (i, stack) in stacks_output.iter_mut().enumerate() {
             let base = i * 4;
-            let c = row.as_bytes()[base + 1] as char;
+            let c = row.as_bytes().get(base + 1).map_or(' ', |b| *b as char);
             if c != ' ' {
-                stacks_output[i].push(c)
+                stack.push(c)
             }
         }
     }
 
     let mut moves: Moves = vec![];
-    let regex = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
-    for m in &lines[stacks_text.len()+1..] {
+    let regex = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
+    for (i, m) in lines.iter().enumerate().skip(stacks_text.len() + 1) {
         if m.is_empty() {
             continue;
         }
 
         let cap = match regex.captures(m) {
-            None => return Err(Box::from("Failed to capture moves")),
+            None => return Err(ParseError::new(5, i + 1, 1, m, "move <count> from <stack> to <stack>")),
             Some(c) => c
         };
 
-        let count: usize = cap.get(1).unwrap().as_str().parse()?;
-        let from: usize = cap.get(2).unwrap().as_str().parse()?;
-        let to: usize = cap.get(3).unwrap().as_str().parse()?;
+        let field = |idx: usize, min: usize, max: usize| -> Result<usize, ParseError> {
+            let group = cap.get(idx).unwrap();
+            match group.as_str().parse::<usize>() {
+                Ok(v) if (min..=max).contains(&v) => Ok(v),
+                _ => Err(ParseError::new(5, i + 1, group.start() + 1, m, &format!("a number from {} to {}", min, max)))
+            }
+        };
+
+        let count = field(1, 0, usize::MAX)?;
+        let from = field(2, 1, stack_count)?;
+        let to = field(3, 1, stack_count)?;
 
         moves.push(Move {
             count,
@@ -60,10 +70,10 @@ fn parse_input(lines: &std::vec::Vec<String>) -> Result<(Stacks, Moves), Box<dyn
     Ok((stacks_output, moves))
 }
 
-fn move_crates(stacks: &mut Stacks, m: &Move, rev_moves: bool) {
-    let base_from = {
-        let st_from: &mut std::vec::Vec<char> = &mut stacks[m.from];
-        st_from.len() - m.count
+pub fn move_crates(stacks: &mut Stacks, m: &Move, rev_moves: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let base_from = match stacks[m.from].len().checked_sub(m.count) {
+        None => return Err(Box::from(format!("Cannot move {} crates from stack {} holding {}", m.count, m.from + 1, stacks[m.from].len()))),
+        Some(b) => b
     };
 
     {
@@ -77,50 +87,63 @@ fn move_crates(stacks: &mut Stacks, m: &Move, rev_moves: bool) {
     }
 
     stacks[m.from].truncate(base_from);
+    crate::trace!("move {} from {} to {}:\n{}", m.count, m.from + 1, m.to + 1, render_stacks(stacks).join("\n"));
+    Ok(())
 }
 
-pub fn print_stacks(stacks: &Stacks) {
+pub fn render_stacks(stacks: &Stacks) -> std::vec::Vec<String> {
     let mx = stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+    let mut rows = vec![];
 
     for i in (0..mx).rev() {
+        let mut row = String::new();
         for s in stacks {
             if i < s.len() {
-                print!(" [{}] ", s[i]);
+                row += &format!(" [{}] ", s[i]);
             }
             else {
-                print!("     ");
+                row += "     ";
             }
         }
-        println!("");
+        rows.push(row);
     }
 
-    for (_, s) in stacks.iter().enumerate() {
-        print!(" ({}) ", s.len());
-    }
-    println!("");
-    for (i, _) in stacks.iter().enumerate() {
-        print!("  {}  ", i);
-    }
+    rows.push(stacks.iter().map(|s| format!(" ({}) ", s.len())).collect());
+    rows.push((0..stacks.len()).map(|i| format!("  {}  ", i)).collect());
+    rows
 }
 
-pub fn run_part1() -> Result<(), Box<dyn std::error::Error>> {
-    let (mut stacks, moves) = parse_input(&read_file2("day5.txt")?)?;
+#[derive(Default)]
+pub struct Solution;
 
-    for m in moves {
-        move_crates(&mut stacks, &m, true);
+impl Solver for Solution {
+    type Parsed = (Stacks, Moves);
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>> {
+        Ok(parse_input(&lines(input))?)
     }
-    print_stacks(&stacks);
-    Ok(())
-}
 
-pub fn run_part2() -> Result<(), Box<dyn std::error::Error>> {
-    let (mut stacks, moves) = parse_input(&read_file2("day5.txt")?)?;
+    fn part1(&self, (stacks, moves): &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let mut stacks = stacks.clone();
 
-    for m in moves {
-        move_crates(&mut stacks, &m, false);
+        for m in moves {
+            move_crates(&mut stacks, m, true)?;
+        }
+        Ok(Answer::Grid(render_stacks(&stacks)))
+    }
+
+    fn part2(&self, (stacks, moves): &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let mut stacks = stacks.clone();
+
+        for m in moves {
+            move_crates(&mut stacks, m, false)?;
+        }
+        Ok(Answer::Grid(render_stacks(&stacks)))
+    }
+
+    fn show(&self, (stacks, moves): &Self::Parsed) -> String {
+        format!("{}\n{} moves", render_stacks(stacks).join("\n"), moves.len())
     }
-    print_stacks(&stacks);
-    Ok(())
 }
 
 #[cfg(test)]
@@ -129,21 +152,21 @@ mod test {
 
     #[test]
     fn test_parse_input() {
-        let contents: std::vec::Vec<String> = vec![
-            "                [B]     [L]     [S]".to_owned(),
-            "        [Q] [J] [C]     [W]     [F]".to_owned(),
-            "    [F] [T] [B] [D]     [P]     [P]".to_owned(),
-            "    [S] [J] [Z] [T]     [B] [C] [H]".to_owned(),
-            "    [L] [H] [H] [Z] [G] [Z] [G] [R]".to_owned(),
-            "[R] [H] [D] [R] [F] [C] [V] [Q] [T]".to_owned(),
-            "[C] [J] [M] [G] [P] [H] [N] [J] [D]".to_owned(),
-            "[H] [B] [R] [S] [R] [T] [S] [R] [L]".to_owned(),
-            " 1   2   3   4   5   6   7   8   9 ".to_owned(),
-            "".to_owned(),
-            "move 8 from 7 to 1".to_owned(),
-            "move 9 from 1 to 9".to_owned(),
-            "move 4 from 5 to 4".to_owned(),
-            "move 4 from 6 to 1".to_owned()];
+        let contents: std::vec::Vec<&str> = vec![
+            "                [B]     [L]     [S]",
+            "        [Q] [J] [C]     [W]     [F]",
+            "    [F] [T] [B] [D]     [P]     [P]",
+            "    [S] [J] [Z] [T]     [B] [C] [H]",
+            "    [L] [H] [H] [Z] [G] [Z] [G] [R]",
+            "[R] [H] [D] [R] [F] [C] [V] [Q] [T]",
+            "[C] [J] [M] [G] [P] [H] [N] [J] [D]",
+            "[H] [B] [R] [S] [R] [T] [S] [R] [L]",
+            " 1   2   3   4   5   6   7   8   9 ",
+            "",
+            "move 8 from 7 to 1",
+            "move 9 from 1 to 9",
+            "move 4 from 5 to 4",
+            "move 4 from 6 to 1"];
 
         let (stacks, moves) = match parse_input(&contents) {
             Ok(x) => x,
@@ -196,7 +219,7 @@ mod test {
             to: 2
         };
 
-        move_crates(&mut stacks, &m, true);
+        move_crates(&mut stacks, &m, true).unwrap();
         assert_eq!(stacks[0], vec!['A', 'B']);
         assert_eq!(stacks[1], vec!['D']);
         assert_eq!(stacks[2], vec!['E', 'F', 'G', 'C']);
@@ -214,7 +237,7 @@ mod test {
                 to: 2
             };
 
-            move_crates(&mut stacks, &m, true);
+            move_crates(&mut stacks, &m, true).unwrap();
             assert_eq!(stacks[0], vec!['A']);
             assert_eq!(stacks[1], vec!['D']);
             assert_eq!(stacks[2], vec!['E', 'F', 'G', 'C', 'B']);
@@ -227,11 +250,41 @@ mod test {
                 to: 1
             };
 
-            move_crates(&mut stacks, &m, true);
+            move_crates(&mut stacks, &m, true).unwrap();
             assert_eq!(stacks[0], vec!['A']);
             assert_eq!(stacks[1], vec!['D', 'B', 'C', 'G', 'F', 'E']);
             assert_eq!(stacks[2], vec![] as std::vec::Vec<char>);
         }
+
+        let m = Move {
+            count: 3,
+            from: 2,
+            to: 1
+        };
+        assert!(move_crates(&mut stacks, &m, true).is_err());
     }
-}
 
+    #[test]
+    fn test_render_stacks() {
+        let stacks: Stacks = vec![vec!['A', 'B'], vec![], vec!['C']];
+
+        assert_eq!(render_stacks(&stacks), vec![
+            " [B]           ".to_owned(),
+            " [A]       [C] ".to_owned(),
+            " (2)  (0)  (1) ".to_owned(),
+            "  0    1    2  ".to_owned()]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+
+        let err = parse_input(&["[A]", "move 1 from 1 to 1"]).err().unwrap();
+        assert_eq!((err.line, err.column), (1, 1));
+
+        let err = parse_input(&["[A] [B]", " 1   2 ", "", "move 1 from 1 to 2", "move 1 from 3 to 1"]).err().unwrap();
+        assert_eq!(err, ParseError::new(5, 5, 13, "move 1 from 3 to 1", "a number from 1 to 2"));
+
+        let err = parse_input(&["[A] [B]", " 1   2 ", "", "move one from 1 to 2"]).err().unwrap();
+        assert_eq!(err, ParseError::new(5, 4, 1, "move one from 1 to 2", "move <count> from <stack> to <stack>"));
+    }
+}
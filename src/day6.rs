@@ -1,10 +1,11 @@
-use crate::common::read_file;
+use crate::common::ParseError;
+use crate::solver::{Answer, Solver};
 
 fn find_last_dup_pair_idx(line: &[u8]) -> Option<usize> {
     let mut seen: u32 = 0;
 
     for i in (0..line.len()).rev() {
-        let mask = 1 << line[i] - b'a';
+        let mask = 1 << (line[i] - b'a');
         if (seen & mask) != 0 {
             return Some(i);
         }
@@ -14,14 +15,14 @@ fn find_last_dup_pair_idx(line: &[u8]) -> Option<usize> {
     None
 }
 
-fn find_first_marker(line: &str, marker_len: usize) -> Result<usize, Box<dyn std::error::Error>> {
+pub fn find_first_marker(line: &str, marker_len: usize) -> Result<usize, Box<dyn std::error::Error>> {
     if line.len() < marker_len {
         return Err(Box::from("Not enough characters"))
     }
 
     let line = line.as_bytes();
     let mut count = marker_len;
-    let mut iter = 0..line.len() - marker_len;
+    let mut iter = 0..=line.len() - marker_len;
     while let Some(idx) = iter.next() {
         match find_last_dup_pair_idx(&line[idx..idx + marker_len]) {
             None => return Ok(count),
@@ -32,21 +33,38 @@ fn find_first_marker(line: &str, marker_len: usize) -> Result<usize, Box<dyn std
         };
     }
 
-    Err(Box::from("Market not found"))
+    Err(Box::from("Marker not found"))
 }
 
-pub fn run_part1() -> Result<(), Box<dyn std::error::Error>> {
-    let line = read_file("day6.txt")?;
-    println!("Marker at: {}", find_first_marker(&line[0], 4)?);
+pub fn parse_signal(input: &str) -> Result<String, ParseError> {
+    let (i, line) = match input.split('\n').enumerate().find(|(_, l)| !l.is_empty()) {
+        None => return Err(ParseError::new(6, 1, 1, "", "a datastream")),
+        Some(x) => x
+    };
 
-    Ok(())
+    match line.char_indices().find(|(_, c)| !c.is_ascii_lowercase()) {
+        None => Ok(line.to_string()),
+        Some((col, _)) => Err(ParseError::at(6, i + 1, line, &line[col..], "a lowercase letter"))
+    }
 }
 
-pub fn run_part2() -> Result<(), Box<dyn std::error::Error>> {
-    let line = read_file("day6.txt")?;
-    println!("Marker at: {}", find_first_marker(&line[0], 14)?);
+#[derive(Default)]
+pub struct Solution;
+
+impl Solver for Solution {
+    type Parsed = String;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>> {
+        Ok(parse_signal(input)?)
+    }
+
+    fn part1(&self, line: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        Answer::try_from(find_first_marker(line, 4)?)
+    }
 
-    Ok(())
+    fn part2(&self, line: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        Answer::try_from(find_first_marker(line, 14)?)
+    }
 }
 
 #[cfg(test)]
@@ -70,6 +88,10 @@ mod test {
         assert_eq!(find_first_marker("nppdvjthqldpwncqszvftbrmjlhg", 4).unwrap(), 6);
         assert_eq!(find_first_marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 4).unwrap(), 10);
         assert_eq!(find_first_marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 4).unwrap(), 11);
+
+        assert_eq!(find_first_marker("abcd", 4).unwrap(), 4);
+        assert_eq!(find_first_marker("aabcd", 4).unwrap(), 5);
+        assert_eq!(find_first_marker("aabc", 4).unwrap_err().to_string(), "Marker not found");
     }
 
     #[test]
@@ -80,4 +102,11 @@ mod test {
         assert_eq!(find_first_marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 14).unwrap(), 29);
         assert_eq!(find_first_marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 14).unwrap(), 26);
     }
+
+    #[test]
+    fn test_parse_signal() {
+        assert_eq!(parse_signal("\nmjqj\n").unwrap(), "mjqj");
+        assert_eq!(parse_signal("").unwrap_err(), ParseError::new(6, 1, 1, "", "a datastream"));
+        assert_eq!(parse_signal("mjQj").unwrap_err(), ParseError::new(6, 1, 3, "mjQj", "a lowercase letter"));
+    }
 }
@@ -1,15 +1,17 @@
-use crate::common::read_file;
+use crate::common::{lines, ParseError};
+use crate::solver::{Answer, Solver};
 
-struct Dir {
-    name: String,
-    file_size: usize,
-    total_size: usize,
+#[derive(Debug)]
+pub struct Dir {
+    pub name: String,
+    pub file_size: usize,
+    pub total_size: usize,
 
-    dirs: std::vec::Vec<Dir>
+    pub dirs: std::vec::Vec<Dir>
 }
 
 impl Dir {
-    fn new(name: &str) -> Dir {
+    pub fn new(name: &str) -> Dir {
         Dir {
             name: name.to_string(),
             file_size: 0,
@@ -25,66 +27,124 @@ impl Dir {
     }
 }
 
-fn parse(dir: &mut Dir, lines: &[String]) -> usize {
+// Deep enough for any real transcript, shallow enough that the recursive parse and the
+// walks over the tree can't overflow the stack.
+const MAX_DEPTH: usize = 1000;
+
+fn parse(dir: &mut Dir, lines: &[&str], first_line: usize) -> Result<usize, ParseError> {
+    let re = regex::Regex::new(r#"(\d+) (.+)"#).unwrap();
+    parse_dir(dir, lines, first_line, 0, &re)
+}
+
+fn parse_dir(dir: &mut Dir, lines: &[&str], first_line: usize, depth: usize, re: &regex::Regex) -> Result<usize, ParseError> {
     let mut i = 0;
     let len = lines.len();
 
-    let re = regex::Regex::new(r#"(\d+) (.+)"#).unwrap();
     while i < len {
         let ln = &lines[i];
-        match &ln[..4] {
+        let line_no = first_line + i;
+        if ln.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        match ln.get(..4).unwrap_or(ln) {
             "$ cd" =>  {
-                let dir_name = &ln[5..];
+                let dir_name = ln.get(5..).unwrap_or("");
                 if dir_name == ".." {
-                    return i + 1;
+                    return Ok(i + 1);
+                }
+
+                if depth == MAX_DEPTH {
+                    return Err(ParseError::at(7, line_no, ln, dir_name, &format!("directories nested at most {} deep", MAX_DEPTH)));
                 }
 
                 let mut found = false;
                 for d in &mut dir.dirs {
                     if d.name == dir_name {
-                        i += parse(d, &lines[i+1..]);
+                        i += parse_dir(d, &lines[i+1..], line_no + 1, depth + 1, re)?;
                         found = true;
                         break;
                     }
                 }
-                assert!(found);
+
+                if !found {
+                    return Err(ParseError::at(7, line_no, ln, dir_name, "a directory listed by ls"));
+                }
             },
             "$ ls" => {
             },
             "dir " => dir.add_dir(&ln[4..]),
             _ => {
                 let cap = match re.captures(ln) {
-                    None => panic!("Failed to capture: {}", ln),
+                    None => return Err(ParseError::new(7, line_no, 1, ln, "a command, dir entry or <size> <name>")),
                     Some(x) => x
                 };
-                let size: usize = cap.get(1).unwrap().as_str().parse().unwrap();
+                let size_match = cap.get(1).unwrap();
+                let size: usize = match size_match.as_str().parse() {
+                    Ok(v) => v,
+                    Err(_) => return Err(ParseError::new(7, line_no, size_match.start() + 1, ln, "a file size"))
+                };
                 let _filename: String = cap.get(2).unwrap().as_str().to_string();
-                dir.file_size += size;
+                dir.file_size = match dir.file_size.checked_add(size) {
+                    None => return Err(ParseError::new(7, line_no, size_match.start() + 1, ln, "a file size that keeps the directory total in range")),
+                    Some(v) => v
+                };
             }
         }
 
         i += 1;
     }
 
-    i
+    Ok(i)
+}
+
+pub fn parse_transcript(input: &str) -> Result<Dir, ParseError> {
+    let lines = lines(input);
+    let first = lines.first().copied().unwrap_or("");
+    if first != "$ cd /" {
+        return Err(ParseError::new(7, 1, 1, first, "'$ cd /'"));
+    }
+
+    let mut d = Dir::new("/");
+    parse(&mut d, &lines[1..], 2)?;
+    match sum_totals(&mut d) {
+        None => Err(ParseError::new(7, 1, 1, first, "directory sizes that add up to a total in range")),
+        Some(_) => Ok(d)
+    }
 }
 
-fn sum_totals(d: &mut Dir) -> usize {
+// None if a directory's total size overflows.
+pub fn sum_totals(d: &mut Dir) -> Option<usize> {
     if d.dirs.is_empty() {
         d.total_size = d.file_size;
-        return d.file_size
+        return Some(d.file_size)
     }
 
     let mut total = d.file_size;
     for c in &mut d.dirs {
-        total += sum_totals(c);
+        total = total.checked_add(sum_totals(c)?)?;
     }
 
     d.total_size = total;
-    total
+    Some(total)
 }
 
-fn total_undersized_dirs(d: &Dir) -> usize {
+// One line per directory, children indented under their parent.
+pub fn render_tree(d: &Dir) -> std::vec::Vec<String> {
+    let mut lines = vec![];
+    render_dir(d, 0, &mut lines);
+    lines
+}
+
+fn render_dir(d: &Dir, depth: usize, lines: &mut std::vec::Vec<String>) {
+    lines.push(format!("{}{} (files {}, total {})", "  ".repeat(depth), d.name, d.file_size, d.total_size));
+    for c in &d.dirs {
+        render_dir(c, depth + 1, lines);
+    }
+}
+
+pub fn total_undersized_dirs(d: &Dir) -> usize {
     let mut total = 0;
     if d.total_size < 100000 {
         total = d.total_size;
@@ -97,7 +157,7 @@ fn total_undersized_dirs(d: &Dir) -> usize {
     total
 }
 
-fn find_smallest_over_threshold(d: &Dir, threshold: usize) -> Option<usize> {
+pub fn find_smallest_over_threshold(d: &Dir, threshold: usize) -> Option<usize> {
     if d.total_size < threshold {
         return None
     }
@@ -114,29 +174,35 @@ fn find_smallest_over_threshold(d: &Dir, threshold: usize) -> Option<usize> {
     Some(candidate)
 }
 
-pub fn run_part1() -> Result<(), Box<dyn std::error::Error>> {
-    let lines = read_file("day7.txt")?;
-    let mut d = Dir::new("/");
-    parse(&mut d, &lines[1..]);
-    sum_totals(&mut d);
-    println!("{}", total_undersized_dirs(&d));
-    Ok(())
-}
+#[derive(Default)]
+pub struct Solution;
 
-pub fn run_part2() -> Result<(), Box<dyn std::error::Error>> {
-    let lines = read_file("day7.txt")?;
-    let mut d = Dir::new("/");
-    parse(&mut d, &lines[1..]);
-    let total_size = sum_totals(&mut d);
+impl Solver for Solution {
+    type Parsed = Dir;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>> {
+        Ok(parse_transcript(input)?)
+    }
 
-    let free_space = 70000000 - total_size;
-    let required_free = 30000000 - free_space;
-    match find_smallest_over_threshold(&d, required_free) {
-        None => println!("Not found"),
-        Some(sz) => println!("Dir size: {}", sz)
-    };
+    fn part1(&self, d: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        Answer::try_from(total_undersized_dirs(d))
+    }
 
-    Ok(())
+    fn part2(&self, d: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let free_space = match 70000000usize.checked_sub(d.total_size) {
+            None => return Err(Box::from("Directory tree is larger than the disk")),
+            Some(free) => free
+        };
+        let required_free = 30000000usize.saturating_sub(free_space);
+        match find_smallest_over_threshold(d, required_free) {
+            None => Err(Box::from("No directory large enough to free")),
+            Some(sz) => Answer::try_from(sz)
+        }
+    }
+
+    fn show(&self, d: &Self::Parsed) -> String {
+        render_tree(d).join("\n")
+    }
 }
 
 #[cfg(test)]
@@ -145,26 +211,26 @@ mod test {
 
     #[test]
     fn test_file_size() {
-        let contents: std::vec::Vec<String> = vec![
-            "$ ls".to_owned(),
-            "dir a".to_owned(),
-            "100 b.txt".to_owned(),
-            "250 c.dat".to_owned()];
+        let contents: std::vec::Vec<&str> = vec![
+            "$ ls",
+            "dir a",
+            "100 b.txt",
+            "250 c.dat"];
 
         let mut d = Dir::new("/");
-        assert_eq!(parse(&mut d, &contents), 4);
+        assert_eq!(parse(&mut d, &contents, 1).unwrap(), 4);
         assert_eq!(d.file_size, 350);
     }
 
     #[test]
     fn test_dir() {
-        let contents: std::vec::Vec<String> = vec![
-            "$ ls".to_owned(),
-            "dir a".to_owned(),
-            "dir d".to_owned()];
+        let contents: std::vec::Vec<&str> = vec![
+            "$ ls",
+            "dir a",
+            "dir d"];
 
         let mut d = Dir::new("/");
-        assert_eq!(parse(&mut d, &contents), 3);
+        assert_eq!(parse(&mut d, &contents, 1).unwrap(), 3);
         assert_eq!(d.dirs.len(), 2);
         assert_eq!(d.dirs[0].name, "a");
         assert_eq!(d.dirs[1].name, "d");
@@ -172,24 +238,24 @@ mod test {
 
     #[test]
     fn test_cd() {
-        let contents: std::vec::Vec<String> = vec![
-            "$ ls".to_owned(),
-            "dir a".to_owned(),
-            "dir b".to_owned(),
-            "$ cd a".to_owned(),
-            "$ ls".to_owned(),
-            "dir a".to_owned(),
-            "dir b".to_owned(),
-            "dir c".to_owned(),
-            "$ cd ..".to_owned(),
-            "$ cd b".to_owned(),
-            "$ ls".to_owned(),
-            "dir x".to_owned(),
-            "dir y".to_owned(),
-            "dir z".to_owned()];
+        let contents: std::vec::Vec<&str> = vec![
+            "$ ls",
+            "dir a",
+            "dir b",
+            "$ cd a",
+            "$ ls",
+            "dir a",
+            "dir b",
+            "dir c",
+            "$ cd ..",
+            "$ cd b",
+            "$ ls",
+            "dir x",
+            "dir y",
+            "dir z"];
 
         let mut d = Dir::new("/");
-        assert_eq!(parse(&mut d, &contents), 14);
+        assert_eq!(parse(&mut d, &contents, 1).unwrap(), 14);
         assert_eq!(d.dirs.len(), 2);
         assert_eq!(d.dirs[0].name, "a");
         assert_eq!(d.dirs[1].name, "b");
@@ -208,23 +274,23 @@ mod test {
 
     #[test]
     fn test_nested_file_size() {
-        let contents: std::vec::Vec<String> = vec![
-            "$ ls".to_owned(),
-            "dir a".to_owned(),
-            "dir b".to_owned(),
-            "$ 20 file".to_owned(),
-            "$ cd a".to_owned(),
-            "$ ls".to_owned(),
-            "$ 100 file1".to_owned(),
-            "$ 200 file2".to_owned(),
-            "$ cd ..".to_owned(),
-            "$ cd b".to_owned(),
-            "$ ls".to_owned(),
-            "$ 300 file1".to_owned(),
-            "$ 400 file2".to_owned(),
+        let contents: std::vec::Vec<&str> = vec![
+            "$ ls",
+            "dir a",
+            "dir b",
+            "$ 20 file",
+            "$ cd a",
+            "$ ls",
+            "$ 100 file1",
+            "$ 200 file2",
+            "$ cd ..",
+            "$ cd b",
+            "$ ls",
+            "$ 300 file1",
+            "$ 400 file2",
         ];
         let mut d = Dir::new("/");
-        assert_eq!(parse(&mut d, &contents), 13);
+        assert_eq!(parse(&mut d, &contents, 1).unwrap(), 13);
         assert_eq!(d.dirs.len(), 2);
 
         let a = &d.dirs[0];
@@ -238,32 +304,32 @@ mod test {
     #[test]
     fn test() {
         let content = vec![
-            "$ cd /".to_owned(),
-            "$ ls".to_owned(),
-            "dir a".to_owned(),
-            "14848514 b.txt".to_owned(),
-            "8504156 c.dat".to_owned(),
-            "dir d".to_owned(),
-            "$ cd a".to_owned(),
-            "$ ls".to_owned(),
-            "dir e".to_owned(),
-            "29116 f".to_owned(),
-            "2557 g".to_owned(),
-            "62596 h.lst".to_owned(),
-            "$ cd e".to_owned(),
-            "$ ls".to_owned(),
-            "584 i".to_owned(),
-            "$ cd ..".to_owned(),
-            "$ cd ..".to_owned(),
-            "$ cd d".to_owned(),
-            "$ ls".to_owned(),
-            "4060174 j".to_owned(),
-            "8033020 d.log".to_owned(),
-            "5626152 d.ext".to_owned(),
-            "7214296 k".to_owned()];
+            "$ cd /",
+            "$ ls",
+            "dir a",
+            "14848514 b.txt",
+            "8504156 c.dat",
+            "dir d",
+            "$ cd a",
+            "$ ls",
+            "dir e",
+            "29116 f",
+            "2557 g",
+            "62596 h.lst",
+            "$ cd e",
+            "$ ls",
+            "584 i",
+            "$ cd ..",
+            "$ cd ..",
+            "$ cd d",
+            "$ ls",
+            "4060174 j",
+            "8033020 d.log",
+            "5626152 d.ext",
+            "7214296 k"];
 
         let mut r = Dir::new("/");
-        parse(&mut r, &content[1..]);
+        parse(&mut r, &content[1..], 2).unwrap();
 
         sum_totals(&mut r);
 
@@ -276,6 +342,12 @@ mod test {
         assert_eq!(d.total_size, 24933642);
         assert_eq!(r.total_size, 48381165);
 
+        assert_eq!(render_tree(&r), vec![
+            "/ (files 23352670, total 48381165)",
+            "  a (files 94269, total 94853)",
+            "    e (files 584, total 584)",
+            "  d (files 24933642, total 24933642)"]);
+
         assert_eq!(total_undersized_dirs(&r), 95437);
         assert_eq!(find_smallest_over_threshold(&r, 8381165), Some(24933642));
     }
@@ -310,7 +382,26 @@ mod test {
             a
         });
 
-        assert_eq!(sum_totals(&mut d), 100);
+        assert_eq!(sum_totals(&mut d), Some(100));
     }
-}
 
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_transcript("$ ls\n").err().unwrap(), ParseError::new(7, 1, 1, "$ ls", "'$ cd /'"));
+        assert_eq!(parse_transcript("$ cd /\n$ ls\ndir a\n$ cd b").err().unwrap(),
+            ParseError::new(7, 4, 6, "$ cd b", "a directory listed by ls"));
+        assert_eq!(parse_transcript("$ cd /\n$ ls\nab").err().unwrap(),
+            ParseError::new(7, 3, 1, "ab", "a command, dir entry or <size> <name>"));
+
+        let max = usize::MAX;
+        assert_eq!(parse_transcript(&format!("$ cd /\n$ ls\n{} a\n{} b\n", max, max)).err().unwrap(),
+            ParseError::new(7, 4, 1, &format!("{} b", max), "a file size that keeps the directory total in range"));
+        assert_eq!(parse_transcript(&format!("$ cd /\n$ ls\n{} a\ndir d\n$ cd d\n$ ls\n1 b\n", max)).err().unwrap(),
+            ParseError::new(7, 1, 1, "$ cd /", "directory sizes that add up to a total in range"));
+
+        let deep = "$ ls\ndir a\n$ cd a\n".repeat(MAX_DEPTH + 1);
+        let err = parse_transcript(&format!("$ cd /\n{}", deep)).err().unwrap();
+        assert_eq!((err.line, err.column), (3 * MAX_DEPTH + 4, 6));
+        assert!(parse_transcript(&format!("$ cd /\n{}", "$ ls\ndir a\n$ cd a\n".repeat(MAX_DEPTH))).is_ok());
+    }
+}
@@ -1,23 +1,20 @@
-use crate::common::read_file;
+use crate::common::{Direction, Grid, ParseError};
+use crate::solver::{Answer, Solver};
 
-type Forest = std::vec::Vec<std::vec::Vec<u8>>;
+pub type Forest = Grid<u8>;
 
-fn set_visible<'a, I>(iter: I) where I: Iterator<Item = &'a mut u8>
-{
-    let mut tallest = 0u8;
-    for v in iter {
-        if (*v & 0x7f) > tallest {
-            tallest = *v & 0x7f;
-            *v |= 0x80; 
-        }
-    }
+fn is_visible(forest: &Forest, row: usize, col: usize) -> bool {
+    let height = forest[(row, col)];
+
+    Direction::ORTHOGONAL.iter().any(|&dir| forest.ray(row, col, dir).all(|pos| forest[pos] < height))
 }
 
-fn calc_scenic_score_iter<'a, I>(iter: I, max_height: u8) -> usize where I: Iterator<Item = &'a u8> {
+fn calc_scenic_score_dir(forest: &Forest, row: usize, col: usize, dir: Direction) -> usize {
+    let height = forest[(row, col)];
     let mut score = 0;
-    for v in iter {
+    for pos in forest.ray(row, col, dir) {
         score += 1;
-        if *v >= max_height {
+        if forest[pos] >= height {
             break;
         }
     }
@@ -25,74 +22,61 @@ fn calc_scenic_score_iter<'a, I>(iter: I, max_height: u8) -> usize where I: Iter
     score
 }
 
-fn calc_scenic_score(forest: &Forest, row: usize, col: usize) -> usize {
-    let height = forest[row][col];
-
-    let up = calc_scenic_score_iter(forest[0..row].iter().map(|r| &r[col]).rev(), height);
-    let down = calc_scenic_score_iter(forest[row+1..forest.len()].iter().map(|r| &r[col]), height);
-
-    let left = calc_scenic_score_iter(forest[row][0..col].iter().rev(), height);
-    let right = calc_scenic_score_iter(forest[row][col+1..forest[row].len()].iter(), height);
-    
-    up * down * left * right
+pub fn calc_scenic_score(forest: &Forest, row: usize, col: usize) -> usize {
+    Direction::ORTHOGONAL.iter().map(|&dir| calc_scenic_score_dir(forest, row, col, dir)).product()
 }
 
-pub fn generate_forest() -> Result<Forest, Box<dyn std::error::Error>> {
-    let lines = read_file("day8.txt")?;
-
-    let forest = lines.iter()
-        .map(|ln| ln.as_bytes().iter().map(|v| v - b'0').collect())
-        .collect();
-
-    Ok(forest)
+pub fn generate_forest(input: &str) -> Result<Forest, ParseError> {
+    Grid::parse(8, input, "a tree height 0-9", |c| c.to_digit(10).map(|d| d as u8))
 }
 
-pub fn run_part1() -> Result<(), Box<dyn std::error::Error>> {
-    let mut forest = generate_forest()?;
-
-    let row_1 = forest.len() - 1;
-    let col_1 = forest[0].len() - 1;
+#[derive(Default)]
+pub struct Solution;
 
-    for v in forest[0].iter_mut() { *v |= 0x80; }
-    for v in &mut forest[row_1].iter_mut() { *v |= 0x80; }
+impl Solver for Solution {
+    type Parsed = Forest;
 
-    for row in &mut forest[1..row_1] {
-        row[0] |= 0x80;
-        row[col_1] |= 0x80;
+    fn show(&self, forest: &Self::Parsed) -> String {
+        forest.render(|&h| (b'0' + h) as char).join("\n")
     }
 
-    for r in &mut forest {
-        set_visible(r.iter_mut());
-        set_visible(r.iter_mut().rev());
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>> {
+        Ok(generate_forest(input)?)
     }
 
-    for col in 0..forest[0].len() {
-        set_visible(forest.iter_mut().map(|r| &mut r[col]));
-        set_visible(forest.iter_mut().map(|r| &mut r[col]).rev());
+    fn part1(&self, forest: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        Answer::try_from(count_visible(forest))
     }
 
-    let count = forest.iter()
-                    .flat_map(|row| row.iter())
-                    .filter(|v| (*v & 0x80) == 0x80)
-                    .count();
-
-    println!("Count: {}", count);
-    Ok(())
+    fn part2(&self, forest: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        Answer::try_from(max_scenic_score(forest))
+    }
 }
 
-pub fn run_part2() -> Result<(), Box<dyn std::error::Error>> {
-    let forest = generate_forest()?;
+pub fn count_visible(forest: &Forest) -> usize {
+    forest.positions()
+        .filter(|&(row, col)| is_visible(forest, row, col))
+        .count()
+}
 
-    let mut max_score = 0;
+pub fn max_scenic_score(forest: &Forest) -> usize {
+    forest.positions()
+        .map(|(row, col)| calc_scenic_score(forest, row, col))
+        .max()
+        .unwrap_or(0)
+}
 
-    let col_len = forest[0].len() - 1;
-    for row in 0..forest.len()-1 {
-        for col in 0..col_len {
-            max_score = std::cmp::max(max_score, calc_scenic_score(&forest, row, col));
-        }
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_generate_forest() {
+        let forest = generate_forest("30\n25\n").unwrap();
+        assert_eq!(forest.row(0).copied().collect::<std::vec::Vec<_>>(), vec![3, 0]);
+        assert_eq!(forest.row(1).copied().collect::<std::vec::Vec<_>>(), vec![2, 5]);
+        assert_eq!(generate_forest("30\n2x").unwrap_err(), ParseError::new(8, 2, 2, "2x", "a tree height 0-9"));
+        assert_eq!(generate_forest("30\n255").unwrap_err(), ParseError::new(8, 2, 1, "255", "a row of width 2"));
+        assert!(generate_forest("\n").is_err());
     }
-
-    println!("Max score: {}", max_score);
-    Ok(())
 }
-
@@ -1,151 +1,137 @@
 use std::collections::HashSet;
 
-use crate::common::read_file;
-
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
-struct Vec2d {
-    x: i32,
-    y: i32
-}
-
-impl Vec2d {
-    fn new(x: i32, y: i32) -> Vec2d {
-        Vec2d { x, y }
-    }
-
-    fn surroundings(&self) -> HashSet<Vec2d> {
-        let mut hash = HashSet::<Vec2d>::new();
-        for x in -1..=1 {
-            for y in -1..=1 {
-                hash.insert(Vec2d::new(self.x + x, self.y + y));         
-            }
-        }
-
-        hash
-    }
-}
+use crate::common::{lines, Direction, ParseError, Point};
+use crate::solver::{Answer, Solver};
 
 #[derive(Clone, Copy, Debug)]
-struct Rope {
-    head: Vec2d,
-    tail: Vec2d
+pub struct Rope {
+    pub head: Point,
+    pub tail: Point
 }
 
 impl Rope {
     fn new() -> Rope {
-        let start = Vec2d::new(0,0);
         Rope {
-            head: start,
-            tail: start
+            head: Point::ORIGIN,
+            tail: Point::ORIGIN
         }
     }
 
-    fn move_head(&mut self, dir: &Vec2d) {
-        assert!(dir.x.abs() <= 1);
-        assert!(dir.y.abs() <= 1);
-
+    pub fn move_head(&mut self, dir: Direction) {
         let last_pos = self.head;
-        self.head.x += dir.x;
-        self.head.y += dir.y;
-
-        let x_dist = (self.head.x - self.tail.x).abs();
-        let y_dist = (self.head.y - self.tail.y).abs();
+        self.head = self.head.step(dir);
 
-        if x_dist > 1 || y_dist > 1 {
+        if self.head.chebyshev(&self.tail) > 1 {
             self.tail = last_pos;
         }
     }
 }
 
-fn parse_moves() -> Result<std::vec::Vec<Vec2d>, Box<dyn std::error::Error>> {
-    let lines = read_file("day9.txt")?;
+pub fn parse_moves(input: &str) -> Result<std::vec::Vec<(Direction, usize)>, ParseError> {
+    let lines = lines(input);
 
     let mut result = vec![];
+    let mut head = Point::ORIGIN;
+
+    for (i, ln) in lines.iter().enumerate().filter(|(_, l)| !l.is_empty()) {
+        let (dir, dist_text) = match ln.split_once(' ') {
+            None => return Err(ParseError::at(9, i + 1, ln, &ln[ln.len()..], "' ' followed by a distance")),
+            Some(x) => x
+        };
+
+        let dir = match Direction::from_letter(dir) {
+            None => return Err(ParseError::at(9, i + 1, ln, dir, "U, D, L or R")),
+            Some(d) => d
+        };
 
-    for ln in lines {
-        let (dir, dist) = match ln.split_once(' ') {
-            None => return Err(Box::from("Unable to split line")),
-            Some(x) => (x.0.as_bytes()[0], x.1.parse::<usize>()?)
+        let dist = match dist_text.parse::<usize>() {
+            Ok(d) => d,
+            Err(_) => return Err(ParseError::at(9, i + 1, ln, dist_text, "a distance"))
         };
 
-        let iter = std::iter::repeat(
-            match dir {
-                b'U' => Vec2d::new(0, 1),
-                b'D' => Vec2d::new(0, -1),
-                b'L' => Vec2d::new(-1, 0),
-                b'R' => Vec2d::new(1, 0),
-                _ => return Err(Box::from(format!("Unknown direction: {}", dir as char)))
-            });
+        // Every knot stays within the range the head has covered, so checking the head keeps
+        // all of the rope's i32 coordinates from overflowing.
+        let offset = dir.offset();
+        head = match i32::try_from(dist).ok().and_then(|d| Some(Point::new(
+            head.x.checked_add(offset.x.checked_mul(d)?)?,
+            head.y.checked_add(offset.y.checked_mul(d)?)?))) {
+            None => return Err(ParseError::at(9, i + 1, ln, dist_text, "a distance that keeps the rope within i32 coordinates")),
+            Some(p) => p
+        };
 
-        result.extend(iter.take(dist));
+        result.push((dir, dist));
     }
 
     Ok(result)
 }
 
-pub fn run_part1() -> Result<(), Box<dyn std::error::Error>> {
-    let moves = parse_moves()?;
-    let mut visited = HashSet::new();
+#[derive(Default)]
+pub struct Solution;
+
+impl Solver for Solution {
+    type Parsed = std::vec::Vec<(Direction, usize)>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>> {
+        Ok(parse_moves(input)?)
+    }
+
+    fn part1(&self, moves: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        let mut visited = HashSet::new();
+
+        let mut rope = Rope::new();
+        for &(dir, dist) in moves {
+            for _ in 0..dist {
+                rope.move_head(dir);
+                visited.insert(rope.tail);
+            }
+        }
 
-    let mut rope = Rope::new();
-    for m in moves {
-        rope.move_head(&m);
-        visited.insert(rope.tail);
+        Answer::try_from(visited.len())
     }
 
-    println!("Visited: {}", visited.len());
-    Ok(())
+    fn part2(&self, moves: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>> {
+        Answer::try_from(simulate_long_rope(moves))
+    }
 }
 
-pub fn run_part2() -> Result<(), Box<dyn std::error::Error>> {
-    let mut rope = vec![Vec2d::new(0,0); 10];
+pub fn simulate_long_rope(moves: &[(Direction, usize)]) -> usize {
+    let mut rope = [Point::ORIGIN; 10];
     let mut visited = HashSet::new();
 
-    visited.insert(rope[0]);
-    let moves = parse_moves()?;
-    for m in moves {
-        rope[0].x += m.x;
-        rope[0].y += m.y;
+    visited.insert(rope[9]);
+    for dir in moves.iter().flat_map(|&(dir, dist)| std::iter::repeat_n(dir, dist)) {
+        rope[0] = rope[0].step(dir);
 
         for i in 1..rope.len() {
-            
-            let last = rope[i-1].clone();
-            let s1 = last.surroundings();
-            let s2 = rope[i].surroundings();
-            let intersection: std::vec::Vec<_> = s1.intersection(&s2).collect();
-            if intersection.contains(&&rope[i]) {
+            let last = rope[i-1];
+            if last.chebyshev(&rope[i]) <= 1 {
                 break;
             }
-            else if intersection.len() == 1 {
-                rope[i] = *intersection[0];
-            }
-            else {
-                rope[i] = {
-                    let mut r = None;
-                    for i in 0..intersection.len() {
-                        let v = intersection[i];
-                        let x = v.x - last.x; 
-                        let y = v.y - last.y;
-                        if x == 0 || y == 0 {
-                            r = Some(*v);
-                            break;
-                        }
-                    }
-
-                    match r {
-                        None => return Err(Box::from("Did not find adjacent cell")),
-                        Some(v) => v
-                    }
-                }
-            }
 
-            if i == (rope.len() - 1) {
-                visited.insert(rope[i]);
-            }
+            rope[i] += (last - rope[i]).signum();
         }
+
+        visited.insert(rope[9]);
     }
 
-    println!("Visited: {}", visited.len());
-    Ok(())
+    visited.len()
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_moves() {
+        assert_eq!(parse_moves("R 2\nU 1\n").unwrap(), vec![(Direction::Right, 2), (Direction::Up, 1)]);
+        assert_eq!(parse_moves("R 2\nX 1").unwrap_err(), ParseError::new(9, 2, 1, "X 1", "U, D, L or R"));
+        assert_eq!(parse_moves("R two").unwrap_err(), ParseError::new(9, 1, 3, "R two", "a distance"));
+        assert_eq!(parse_moves("R").unwrap_err(), ParseError::new(9, 1, 2, "R", "' ' followed by a distance"));
+
+        assert_eq!(parse_moves("R 70000\nL 70000").unwrap(), vec![(Direction::Right, 70000), (Direction::Left, 70000)]);
+        assert_eq!(parse_moves("R 2147483647\nL 2147483647\nL 2147483647").unwrap().len(), 3);
+        assert_eq!(parse_moves("U 2147483647\nU 1\nU 1").unwrap_err(),
+            ParseError::new(9, 3, 3, "U 1", "a distance that keeps the rope within i32 coordinates"));
+        assert!(parse_moves("R 1\nR 18446744073709551615").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::answers::matches;
+use crate::solver::{Answer, Part};
+
+pub struct Example {
+    pub day: u32,
+    pub name: &'static str,
+    pub input: &'static str,
+    pub expected: [Option<&'static str>; 2]
+}
+
+impl Example {
+    pub fn expected(&self, part: Part) -> Option<Answer> {
+        let text = self.expected[part.number() as usize - 1]?;
+        if text.contains('\n') {
+            return Some(Answer::Grid(text.split('\n').map(|s| s.to_string()).collect()))
+        }
+
+        match text.parse::<i64>() {
+            Ok(v) => Some(Answer::Int(v)),
+            Err(_) => Some(Answer::Text(text.to_string()))
+        }
+    }
+
+    pub fn check(&self, part: Part, actual: &Answer) -> Option<bool> {
+        self.expected(part).map(|expected| matches(&expected, actual))
+    }
+}
+
+pub static EXAMPLES: [Example; 13] = [
+    Example { day: 1, name: "day1.ex", input: include_str!("../day1.ex"), expected: [Some("24000"), Some("45000")] },
+    Example { day: 2, name: "day2.ex", input: include_str!("../day2.ex"), expected: [Some("15"), Some("12")] },
+    Example { day: 3, name: "day3.ex", input: include_str!("../day3.ex"), expected: [Some("157"), Some("70")] },
+    Example { day: 4, name: "day4.ex", input: include_str!("../day4.ex"), expected: [Some("2"), Some("4")] },
+    Example {
+        day: 5,
+        name: "day5.ex",
+        input: include_str!("../day5.ex"),
+        expected: [
+            Some("           [Z]\n           [N]\n           [D]\n [C]  [M]  [P]\n (1)  (1)  (4)\n  0    1    2"),
+            Some("           [D]\n           [N]\n           [Z]\n [M]  [C]  [P]\n (1)  (1)  (4)\n  0    1    2")]
+    },
+    Example { day: 6, name: "day6.ex", input: include_str!("../day6.ex"), expected: [Some("5"), Some("23")] },
+    Example { day: 7, name: "day7.ex", input: include_str!("../day7.ex"), expected: [Some("95437"), Some("24933642")] },
+    Example { day: 8, name: "day8.ex", input: include_str!("../day8.ex"), expected: [Some("21"), Some("8")] },
+    Example { day: 9, name: "day9.ex", input: include_str!("../day9.ex"), expected: [Some("13"), Some("1")] },
+    Example { day: 9, name: "day9.ex2", input: include_str!("../day9.ex2"), expected: [None, Some("36")] },
+    Example {
+        day: 10,
+        name: "day10.ex",
+        input: include_str!("../day10.ex"),
+        expected: [
+            Some("13140"),
+            Some("##..##..##..##..##..##..##..##..##..##..\n\
+                  ###...###...###...###...###...###...###.\n\
+                  ####....####....####....####....####....\n\
+                  #####.....#####.....#####.....#####.....\n\
+                  ######......######......######......####\n\
+                  #######.......#######.......#######.....")]
+    },
+    Example { day: 11, name: "day11.ex", input: include_str!("../day11.ex"), expected: [Some("10605"), Some("2713310158")] },
+    Example { day: 12, name: "day12.ex", input: include_str!("../day12.ex"), expected: [Some("31"), Some("29")] }
+];
+
+pub fn for_day(day: u32) -> impl Iterator<Item = &'static Example> {
+    EXAMPLES.iter().filter(move |e| e.day == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solver::{DAYS, PARTS};
+
+    #[test]
+    fn test_every_day_has_an_example() {
+        for day in &DAYS {
+            assert!(for_day(day.day).next().is_some(), "no example for day {}", day.day);
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        for example in &EXAMPLES {
+            let day = &DAYS[example.day as usize - 1];
+            let puzzle = day.parse(example.input).unwrap();
+            for part in PARTS {
+                if let Some(expected) = example.expected(part) {
+                    let actual = puzzle.solve(part).unwrap();
+                    assert!(matches(&expected, &actual), "{} part {}: expected {}, got {}", example.name, part.number(), expected, actual);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_windows_line_endings() {
+        for example in &EXAMPLES {
+            let day = &DAYS[example.day as usize - 1];
+            let windows = format!("\u{feff}{}", example.input.trim_end().replace('\n', "  \r\n"));
+            let puzzle = day.parse(&windows).unwrap_or_else(|e| panic!("{}: {}", example.name, e));
+            for part in PARTS {
+                if let Some(expected) = example.expected(part) {
+                    assert!(matches(&expected, &puzzle.solve(part).unwrap()), "{} part {}", example.name, part.number());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use crate::examples;
+use crate::generate::{generate, Rng};
+use crate::solver::{Day, PARTS};
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+pub struct Crash {
+    pub day: u32,
+    pub phase: String,
+    pub message: String,
+    pub input: String
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    }
+    else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    }
+    else {
+        "non-string panic".to_string()
+    }
+}
+
+// Solving can rightly take time in proportion to the numbers in the input (day 9 walks its
+// rope a step at a time), so a mutant with numbers far beyond the corpus's is only parsed.
+const NUMBER_GROWTH: u64 = 1000;
+
+// None if some number doesn't even fit in a u64.
+fn largest_number(input: &str) -> Option<u64> {
+    input.split(|c: char| !c.is_ascii_digit())
+        .filter(|n| !n.is_empty())
+        .try_fold(0, |max, n| n.parse::<u64>().ok().map(|v| v.max(max)))
+}
+
+// Parses and solves both parts; returns the phase that panicked and the panic message.
+fn run_case(day: &Day, input: &str, number_limit: u64) -> Option<(String, String)> {
+    let mut phase = "parse".to_string();
+    let solve = largest_number(input).is_some_and(|n| n <= number_limit);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let parsed = day.parse(input);
+        if !solve {
+            return
+        }
+
+        if let Ok(puzzle) = parsed {
+            for part in PARTS {
+                phase = format!("part {}", part.number());
+                let _ = puzzle.solve(part);
+            }
+        }
+    }));
+
+    result.err().map(|payload| (phase, panic_message(payload.as_ref())))
+}
+
+const INTERESTING: &[&str] = &["0", "9", "-", " ", "\n", "\n\n", ",", ":", "$ ", "dir ", "old", "99999999999", "18446744073709551615", "é", "\r"];
+
+fn char_boundary(s: &str, mut i: usize) -> usize {
+    while !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+pub fn mutate(rng: &mut Rng, input: &str) -> String {
+    let mut out = input.to_string();
+
+    for _ in 0..rng.range(1, 4) {
+        let len = out.len();
+        let at = char_boundary(&out, rng.below(len + 1));
+
+        match rng.below(7) {
+            0 => out.insert_str(at, rng.pick(INTERESTING)),
+            1 => {
+                let end = char_boundary(&out, (at + rng.range(1, 8)).min(len));
+                out.replace_range(at..end, "");
+            },
+            2 => out.truncate(at),
+            3 => {
+                let c = rng.pick(b"0123456789abcxyzABCXYZ $-,:[]\n") as char;
+                let end = char_boundary(&out, (at + 1).min(len));
+                out.replace_range(at..end, &c.to_string());
+            },
+            4 => {
+                let lines: std::vec::Vec<&str> = out.split('\n').collect();
+                let line = rng.pick(&lines).to_string();
+                out.insert_str(at, &(line + "\n"));
+            },
+            5 => {
+                let mut lines: std::vec::Vec<&str> = out.split('\n').collect();
+                let (i, j) = (rng.below(lines.len()), rng.below(lines.len()));
+                lines.swap(i, j);
+                out = lines.join("\n");
+            },
+            _ => {
+                let end = char_boundary(&out, (at + rng.range(1, 8)).min(len));
+                let chunk = out[at..end].to_string();
+                out.insert_str(at, &chunk);
+            }
+        }
+    }
+
+    out
+}
+
+// Greedily removes lines, then characters, as long as the same phase still panics.
+pub fn minimize(day: &Day, input: &str, phase: &str, number_limit: u64) -> String {
+    let still_crashes = |candidate: &str| run_case(day, candidate, number_limit).is_some_and(|(p, _)| p == phase);
+    let mut current = input.to_string();
+
+    let mut i = 0;
+    loop {
+        let lines: std::vec::Vec<&str> = current.split('\n').collect();
+        if i >= lines.len() {
+            break;
+        }
+
+        let candidate = lines.iter().enumerate().filter(|&(j, _)| j != i).map(|(_, l)| *l).collect::<std::vec::Vec<_>>().join("\n");
+        if still_crashes(&candidate) {
+            current = candidate;
+        }
+        else {
+            i += 1;
+        }
+    }
+
+    let mut chars: std::vec::Vec<char> = current.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let mut candidate = chars.clone();
+        candidate.remove(i);
+        if still_crashes(&candidate.iter().collect::<String>()) {
+            chars = candidate;
+        }
+        else {
+            i += 1;
+        }
+    }
+
+    chars.into_iter().collect()
+}
+
+pub fn fuzz_day(day: &Day, seed: u64, iterations: usize) -> Option<Crash> {
+    let mut corpus: std::vec::Vec<String> = examples::for_day(day.day).map(|e| e.input.to_string()).collect();
+    corpus.extend((0..4).filter_map(|i| generate(day.day, seed.wrapping_add(i), 8)));
+
+    let number_limit = corpus.iter()
+        .filter_map(|c| largest_number(c))
+        .max()
+        .unwrap_or(0)
+        .max(1)
+        .saturating_mul(NUMBER_GROWTH);
+
+    let mut rng = Rng::new(seed);
+    for _ in 0..iterations {
+        let base = &corpus[rng.below(corpus.len())];
+        let input = mutate(&mut rng, base);
+
+        if let Some((phase, message)) = run_case(day, &input, number_limit) {
+            return Some(Crash {
+                day: day.day,
+                input: minimize(day, &input, &phase, number_limit),
+                phase,
+                message
+            })
+        }
+    }
+
+    None
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+// Hides panic messages on the current thread until dropped. The hook is wrapped once rather
+// than swapped back and forth, because set_hook can't be called while a panic unwinds.
+struct QuietPanics;
+
+impl QuietPanics {
+    fn new() -> QuietPanics {
+        static WRAP_HOOK: Once = Once::new();
+        WRAP_HOOK.call_once(|| {
+            let hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                if !QUIET.with(|q| q.get()) {
+                    hook(info);
+                }
+            }));
+        });
+
+        QUIET.with(|q| q.set(true));
+        QuietPanics
+    }
+}
+
+impl Drop for QuietPanics {
+    fn drop(&mut self) {
+        QUIET.with(|q| q.set(false));
+    }
+}
+
+pub fn fuzz(days: &[Day], seed: u64, iterations: usize, quiet: bool) -> std::vec::Vec<Crash> {
+    let _quiet = quiet.then(QuietPanics::new);
+    days.iter().filter_map(|day| fuzz_day(day, seed, iterations)).collect()
+}
+
+pub fn print_crashes(crashes: &[Crash]) {
+    for crash in crashes {
+        println!("Day {} {} panicked: {}", crash.day, crash.phase, crash.message);
+        println!("  minimized input: {:?}", crash.input);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solver::DAYS;
+
+    #[test]
+    fn test_mutate() {
+        let mut rng = Rng::new(1);
+        for _ in 0..200 {
+            mutate(&mut rng, "abc\ndéf\n");
+        }
+        assert_eq!(mutate(&mut Rng::new(5), "abc\n"), mutate(&mut Rng::new(5), "abc\n"));
+    }
+
+    #[test]
+    fn test_quiet_panics() {
+        let result = panic::catch_unwind(|| {
+            let _quiet = QuietPanics::new();
+            assert!(QUIET.with(|q| q.get()));
+            panic!("not shown");
+        });
+        assert!(result.is_err());
+        assert!(!QUIET.with(|q| q.get()));
+    }
+
+    #[test]
+    fn test_largest_number() {
+        assert_eq!(largest_number("R 4\nU 12\n-3"), Some(12));
+        assert_eq!(largest_number("no numbers"), Some(0));
+        assert_eq!(largest_number("1 18446744073709551616"), None);
+    }
+
+    #[test]
+    fn test_parsers_are_total() {
+        if let Some(crash) = fuzz(&DAYS, 0, 300, false).first() {
+            panic!("day {} {} panicked on {:?}: {}", crash.day, crash.phase, crash.input, crash.message);
+        }
+    }
+}
@@ -0,0 +1,343 @@
+pub struct Rng {
+    state: u64
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    // SplitMix64
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn range(&mut self, lo: usize, hi: usize) -> usize {
+        lo + self.below(hi - lo + 1)
+    }
+
+    pub fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+const LOWER: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPER: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+fn day1(rng: &mut Rng, size: usize) -> String {
+    let elves: std::vec::Vec<String> = (0..size.max(1))
+        .map(|_| (0..rng.range(1, 6)).map(|_| format!("{}\n", rng.range(1000, 60000))).collect())
+        .collect();
+    elves.join("\n")
+}
+
+fn day2(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| format!("{} {}\n", rng.pick(&['A', 'B', 'C']), rng.pick(&['X', 'Y', 'Z'])))
+        .collect()
+}
+
+fn day3(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+
+    for _ in 0..size.max(1) {
+        let mut letters: std::vec::Vec<u8> = LOWER.iter().chain(UPPER).copied().collect();
+        rng.shuffle(&mut letters);
+        let badge = letters.pop().unwrap();
+
+        // Each elf draws from its own pool, so the badge is the only item all three share.
+        // Items are drawn with repeats, so compartments can grow with size past the pool.
+        for pool in letters.chunks(letters.len() / 3).take(3) {
+            let common = pool[0];
+            let (left, right) = pool[1..].split_at((pool.len() - 1) / 2);
+            let half = rng.range(2, left.len().min(right.len()).max(size) + 1);
+
+            let mut first = vec![common, badge];
+            first.extend((2..half).map(|_| rng.pick(left)));
+            let mut second = vec![common];
+            second.extend((1..half).map(|_| rng.pick(right)));
+            rng.shuffle(&mut first);
+            rng.shuffle(&mut second);
+
+            out += &format!("{}{}\n", String::from_utf8(first).unwrap(), String::from_utf8(second).unwrap());
+        }
+    }
+
+    out
+}
+
+fn day4(rng: &mut Rng, size: usize) -> String {
+    let mut range = || {
+        let a = rng.range(1, 99);
+        (a, rng.range(a, 99))
+    };
+
+    (0..size.max(1))
+        .map(|_| {
+            let (a, b) = range();
+            let (c, d) = range();
+            format!("{}-{},{}-{}\n", a, b, c, d)
+        })
+        .collect()
+}
+
+fn day5(rng: &mut Rng, size: usize) -> String {
+    let stack_count = rng.range(3, 9);
+    let mut stacks: std::vec::Vec<std::vec::Vec<u8>> = (0..stack_count)
+        .map(|_| (0..rng.range(1, 8)).map(|_| rng.pick(UPPER)).collect())
+        .collect();
+
+    let height = stacks.iter().map(|s| s.len()).max().unwrap();
+    let mut out = String::new();
+    for level in (0..height).rev() {
+        let row: std::vec::Vec<String> = stacks.iter()
+            .map(|s| s.get(level).map_or("   ".to_string(), |c| format!("[{}]", *c as char)))
+            .collect();
+        out += &row.join(" ");
+        out += "\n";
+    }
+    out += &(1..=stack_count).map(|i| format!(" {} ", i)).collect::<std::vec::Vec<_>>().join(" ");
+    out += "\n\n";
+
+    for _ in 0..size {
+        let non_empty: std::vec::Vec<usize> = (0..stack_count).filter(|&i| !stacks[i].is_empty()).collect();
+        let from = rng.pick(&non_empty);
+        let to = (from + rng.range(1, stack_count - 1)) % stack_count;
+        let count = rng.range(1, stacks[from].len());
+
+        let base = stacks[from].len() - count;
+        let moved = stacks[from].split_off(base);
+        stacks[to].extend(moved);
+        out += &format!("move {} from {} to {}\n", count, from + 1, to + 1);
+    }
+
+    out
+}
+
+fn day6(rng: &mut Rng, size: usize) -> String {
+    let mut signal: std::vec::Vec<u8> = (0..size.max(14)).map(|_| rng.pick(LOWER)).collect();
+
+    let mut marker = LOWER.to_vec();
+    rng.shuffle(&mut marker);
+    let start = rng.below(signal.len() - 13);
+    signal[start..start + 14].copy_from_slice(&marker[..14]);
+
+    String::from_utf8(signal).unwrap() + "\n"
+}
+
+fn day7_listing(entries: &[String], body: &str) -> String {
+    let mut out = String::from("$ ls\n");
+    for entry in entries {
+        out.push_str(entry);
+        out.push('\n');
+    }
+    out + body
+}
+
+// Returns the directory's `ls` entries, the transcript of its subdirectories and its total size.
+fn day7_dir(rng: &mut Rng, budget: &mut usize, max_file: usize, depth: usize) -> (std::vec::Vec<String>, String, usize) {
+    // The root always gets a subdirectory when it can, so part one has directories to sum.
+    let min_dirs = if depth == 0 { 1 } else { 0 };
+    let dir_count = if depth < 8 { rng.range(min_dirs, 4).min(*budget) } else { 0 };
+    *budget -= dir_count;
+
+    let mut entries: std::vec::Vec<String> = (0..dir_count).map(|i| format!("dir d{}", i)).collect();
+    let mut total = 0;
+    for i in 0..rng.range(0, 5) {
+        let file_size = rng.range(1, max_file);
+        total += file_size;
+        entries.push(format!("{} f{}.txt", file_size, i));
+    }
+    rng.shuffle(&mut entries);
+
+    let mut body = String::new();
+    for i in 0..dir_count {
+        let (sub_entries, sub_body, sub_total) = day7_dir(rng, budget, max_file, depth + 1);
+        body += &format!("$ cd d{}\n", i);
+        body += &day7_listing(&sub_entries, &sub_body);
+        body += "$ cd ..\n";
+        total += sub_total;
+    }
+
+    (entries, body, total)
+}
+
+fn day7(rng: &mut Rng, size: usize) -> String {
+    // At most size + 1 directories of at most five files each stay under 50000000.
+    let max_file = (50000000 / (5 * (size + 1))).clamp(1, 300000);
+    let mut budget = size;
+    let (mut entries, body, total) = day7_dir(rng, &mut budget, max_file, 0);
+
+    // Fill the disk past 40000000 so part two has to free some space, like the real input.
+    let target = rng.range(40100000, 41000000);
+    if target > total {
+        entries.push(format!("{} pad.bin", target - total));
+    }
+
+    String::from("$ cd /\n") + &day7_listing(&entries, &body)
+}
+
+fn day8(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    (0..size)
+        .map(|_| (0..size).map(|_| rng.pick(b"0123456789") as char).collect::<String>() + "\n")
+        .collect()
+}
+
+fn day9(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| format!("{} {}\n", rng.pick(&['U', 'D', 'L', 'R']), rng.range(1, 20)))
+        .collect()
+}
+
+fn day10(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            if rng.chance(30) {
+                "noop\n".to_string()
+            }
+            else {
+                format!("addx {}\n", rng.range(0, 40) as i32 - 20)
+            }
+        })
+        .collect()
+}
+
+fn day11(rng: &mut Rng, size: usize) -> String {
+    let mut divisors = vec![2, 3, 5, 7, 11, 13, 17, 19, 23];
+    rng.shuffle(&mut divisors);
+    let count = rng.range(2, 8);
+
+    let mut out = vec![];
+    for (m, div) in divisors.iter().take(count).enumerate() {
+        let items: std::vec::Vec<String> = (0..rng.range(1, size.max(1)))
+            .map(|_| rng.range(40, 99).to_string())
+            .collect();
+
+        let op = if rng.chance(50) {
+            format!("* {}", rng.range(2, 7))
+        }
+        else {
+            format!("+ {}", rng.range(1, 8))
+        };
+
+        // Throwing only to lower monkeys (monkey 0 to any other) means an item is
+        // operated on at most twice per round, which keeps part one inside a u64.
+        let mut target = || if m == 0 { rng.range(1, count - 1) } else { rng.below(m) };
+        let (if_true, if_false) = (target(), target());
+
+        out.push(format!(
+            "Monkey {}:\n  Starting items: {}\n  Operation: new = old {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+            m, items.join(", "), op, div, if_true, if_false));
+    }
+
+    out.join("\n")
+}
+
+fn day12(rng: &mut Rng, size: usize) -> String {
+    // A gradient from a to z climbs at most one step per move once height + width - 2 >= 25.
+    let side = size.max(14);
+    let scale = 2 * side - 2;
+    let mut rows = vec![];
+
+    for r in 0..side {
+        let mut row = String::new();
+        for c in 0..side {
+            let mut height = (r + c) * 25 / scale;
+
+            // The top row and right column stay untouched so there's always a path to E.
+            if r > 0 && c < side - 1 && rng.chance(30) {
+                height = rng.range(0, height);
+            }
+
+            row.push(match (r, c) {
+                (0, 0) => 'S',
+                _ if r == side - 1 && c == side - 1 => 'E',
+                _ => LOWER[height] as char
+            });
+        }
+        rows.push(row + "\n");
+    }
+
+    rows.concat()
+}
+
+pub fn generate(day: u32, seed: u64, size: usize) -> Option<String> {
+    let generator = match day {
+        1 => day1,
+        2 => day2,
+        3 => day3,
+        4 => day4,
+        5 => day5,
+        6 => day6,
+        7 => day7,
+        8 => day8,
+        9 => day9,
+        10 => day10,
+        11 => day11,
+        12 => day12,
+        _ => return None
+    };
+
+    Some(generator(&mut Rng::new(seed), size))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solver::{DAYS, PARTS};
+
+    #[test]
+    fn test_rng() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+            assert!((3..=5).contains(&a.range(3, 5)));
+            b.range(3, 5);
+        }
+    }
+
+    #[test]
+    fn test_generated_inputs_solve() {
+        for day in &DAYS {
+            // (size, seeds): the smallest day 6 signal, the usual size and one large input, which
+            // takes seconds per day in a debug build.
+            for (size, seed) in [(14, 5), (20, 5), (1000, 1)].iter().flat_map(|&(size, seeds)| (0..seeds).map(move |seed| (size, seed))) {
+                let input = generate(day.day, seed, size).unwrap();
+                assert_eq!(generate(day.day, seed, size).unwrap(), input);
+
+                let puzzle = match day.parse(&input) {
+                    Ok(p) => p,
+                    Err(e) => panic!("day {} seed {} size {}: {}", day.day, seed, size, e)
+                };
+                for part in PARTS {
+                    if let Err(e) = puzzle.solve(part) {
+                        panic!("day {} seed {} size {} part {}: {}", day.day, seed, size, part.number(), e);
+                    }
+                }
+            }
+        }
+
+        assert!(generate(13, 0, 20).is_none());
+    }
+}
@@ -0,0 +1,22 @@
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod common;
+pub mod solver;
+pub mod runner;
+pub mod answers;
+pub mod bench;
+pub mod examples;
+pub mod fuzz;
+pub mod generate;
+pub mod repl;
+pub mod watch;
@@ -1,49 +1,446 @@
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod day10;
-mod day11;
-mod day12;
-mod common;
-
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-
-    let env: std::vec::Vec<String> = std::env::args().collect();
-    match env[1].as_str() {
-        "--day1-part1" => day1::run_part1(),
-        "--day1-part2" => day1::run_part2(),
-        "--day2-part1" => day2::run_part1(),
-        "--day2-part2" => day2::run_part2(),
-        "--day3-part1" => day3::run_part1(),
-        "--day3-part2" => day3::run_part2(),
-        "--day4-part1" => day4::run_part1(),
-        "--day4-part2" => day4::run_part2(),
-        "--day5-part1" => day5::run_part1(),
-        "--day5-part2" => day5::run_part2(),
-        "--day6-part1" => day6::run_part1(),
-        "--day6-part2" => day6::run_part2(),
-        "--day7-part1" => day7::run_part1(),
-        "--day7-part2" => day7::run_part2(),
-        "--day8-part1" => day8::run_part1(),
-        "--day8-part2" => day8::run_part2(),
-        "--day9-part1" => day9::run_part1(),
-        "--day9-part2" => day9::run_part2(),
-        "--day10-part1" => day10::run_part1(),
-        "--day10-part2" => day10::run_part2(),
-        "--day11-part1" => day11::run_part1(),
-        "--day11-part2" => day11::run_part2(),
-        "--day12-part1" => day12::run_part1(),
-        "--day12-part2" => day12::run_part2(),
-        _ => {
-            eprintln!("Unknown part");
-            Ok(())
+use aoc::{answers, bench, common, day1, debug, examples, fuzz, generate, info, repl, runner, watch};
+use aoc::common::log;
+use aoc::runner::Format;
+use aoc::solver::{Answer, Day, Part, DAYS, PARTS};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: aoc [-q|-v|-vv] <command> [options]
+
+Commands:
+  run <day> [<part>]   solve one or both parts of a day
+      [--input <path>|-|--example] [--format text|json] [--watch] [--top N]
+      [--stream]         day 1 only: read the input a line at a time in constant memory
+      [--set name=value] set a solver parameter, e.g. day 2's win, draw, lose, rock, paper, scissors
+      [--config <path>]  read solver parameters from a file of name = value lines
+  list                 list the days and their input files
+  all                  solve every day [--jobs N] [--format text|json]
+  verify               check every answer against answers.toml [--jobs N]
+  bench <day>          time a day [--iterations N] [--baseline <path>] [--save-baseline <path>]
+  stats                day 1 calorie statistics [--input <path>|-|--example]
+  generate <day>       print a random input [--seed N] [--size N]
+  fuzz [<day>]         look for panics on mutated inputs [--seed N] [--iterations N]
+  repl                 load inputs and query solvers interactively
+  help                 show this message
+
+The older --list, --all, --verify, --bench and --dayN-partM forms still work.";
+
+#[derive(Debug)]
+struct UsageError(String);
+
+impl std::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+fn usage_error(msg: &str) -> Box<dyn std::error::Error> {
+    Box::new(UsageError(msg.to_string()))
+}
+
+#[derive(Default)]
+struct Options {
+    input: Option<String>,
+    example: bool,
+    watch: bool,
+    iterations: Option<usize>,
+    baseline: Option<String>,
+    save_baseline: Option<String>,
+    seed: Option<u64>,
+    size: Option<usize>,
+    jobs: Option<usize>,
+    format: Option<Format>,
+    top: Option<usize>,
+    stream: bool,
+    params: std::vec::Vec<(String, String)>,
+    config: Option<String>
+}
+
+fn option_value(iter: &mut std::slice::Iter<String>, name: &str) -> Result<String, Box<dyn std::error::Error>> {
+    match iter.next() {
+        None => Err(usage_error(&format!("{} expects a value", name))),
+        Some(v) => Ok(v.clone())
+    }
+}
+
+const RUN_OPTIONS: &[&str] = &["--input", "--example", "--watch", "--stream", "--format", "--top", "--set", "--config"];
+
+// Each command passes the options it understands, so anything else is a usage error rather
+// than being silently ignored.
+fn parse_options(command: &str, args: &[String], allowed: &[&str]) -> Result<Options, Box<dyn std::error::Error>> {
+    let mut options = Options::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if !allowed.contains(&arg.as_str()) {
+            return Err(usage_error(&format!("Unknown option {} for {}", arg, command)))
+        }
+
+        match arg.as_str() {
+            "--input" => options.input = Some(option_value(&mut iter, arg)?),
+            "--example" => options.example = true,
+            "--watch" => options.watch = true,
+            "--stream" => options.stream = true,
+            "--iterations" => match option_value(&mut iter, arg)?.parse::<usize>() {
+                Ok(n) if n > 0 => options.iterations = Some(n),
+                _ => return Err(usage_error("--iterations expects a positive number"))
+            },
+            "--baseline" => options.baseline = Some(option_value(&mut iter, arg)?),
+            "--save-baseline" => options.save_baseline = Some(option_value(&mut iter, arg)?),
+            "--seed" => match option_value(&mut iter, arg)?.parse::<u64>() {
+                Ok(n) => options.seed = Some(n),
+                _ => return Err(usage_error("--seed expects a number"))
+            },
+            "--size" => match option_value(&mut iter, arg)?.parse::<usize>() {
+                Ok(n) => options.size = Some(n),
+                _ => return Err(usage_error("--size expects a number"))
+            },
+            "--jobs" => match option_value(&mut iter, arg)?.parse::<usize>() {
+                Ok(n) if n > 0 => options.jobs = Some(n),
+                _ => return Err(usage_error("--jobs expects a positive number"))
+            },
+            "--format" => match Format::from_name(&option_value(&mut iter, arg)?) {
+                Some(f) => options.format = Some(f),
+                None => return Err(usage_error("--format expects text or json"))
+            },
+            "--top" => match option_value(&mut iter, arg)?.parse::<usize>() {
+                Ok(n) if n > 0 => options.top = Some(n),
+                _ => return Err(usage_error("--top expects a positive number"))
+            },
+            "--set" => match option_value(&mut iter, arg)?.split_once('=') {
+                Some((name, value)) if !name.is_empty() => options.params.push((name.to_string(), value.to_string())),
+                _ => return Err(usage_error("--set expects name=value"))
+            },
+            "--config" => options.config = Some(option_value(&mut iter, arg)?),
+            _ => return Err(usage_error(&format!("Unknown option {}", arg)))
         }
     }
+
+    if options.example && options.input.is_some() {
+        return Err(usage_error("--example and --input cannot be combined"))
+    }
+
+    if options.watch && (options.format == Some(Format::Json) || options.input.as_deref() == Some("-")) {
+        return Err(usage_error("--watch needs text output and an input file"))
+    }
+
+    if options.stream && (options.watch || options.example || options.format == Some(Format::Json)) {
+        return Err(usage_error("--stream cannot be combined with --watch, --example or --format json"))
+    }
+
+    if (!options.params.is_empty() || options.config.is_some()) && options.stream {
+        return Err(usage_error("--set and --config cannot be combined with --stream"))
+    }
+
+    Ok(options)
 }
 
+fn parse_day(arg: Option<&String>, command: &str) -> Result<&'static Day, Box<dyn std::error::Error>> {
+    match arg.and_then(|d| d.parse::<usize>().ok()) {
+        Some(d) if (1..=DAYS.len()).contains(&d) => Ok(&DAYS[d - 1]),
+        _ => Err(usage_error(&format!("{} expects a day from 1 to {}", command, DAYS.len())))
+    }
+}
+
+fn parse_part(arg: &str) -> Result<Part, Box<dyn std::error::Error>> {
+    match arg {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(usage_error(&format!("Expected part 1 or 2, got {}", arg)))
+    }
+}
+
+// Splits an optional leading positional argument (a day or part) from the options after it.
+fn positional(args: &[String]) -> (Option<&String>, &[String]) {
+    match args.first() {
+        Some(a) if !a.starts_with("--") => (Some(a), &args[1..]),
+        _ => (None, args)
+    }
+}
+
+fn legacy_day_flag(flag: &str) -> Option<(&'static Day, Part)> {
+    DAYS.iter()
+        .flat_map(|day| PARTS.map(|part| (day, part)))
+        .find(|(day, part)| day.flag(*part) == flag)
+}
+
+fn list(args: &[String]) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    parse_options("list", args, &[])?;
+    for day in &DAYS {
+        println!("Day {}: {}", day.day, day.input_file());
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn all(args: &[String]) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let options = parse_options("all", args, &["--jobs", "--format"])?;
+    let runs = runner::run_all(options.jobs.unwrap_or_else(runner::default_jobs));
+    match options.format {
+        Some(Format::Json) => runner::print_json(&runs),
+        _ => runner::print_table(&runs)
+    }
+
+    if runs.iter().any(|r| r.answer.is_err()) {
+        return Ok(ExitCode::FAILURE)
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn verify(args: &[String]) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let options = parse_options("verify", args, &["--jobs"])?;
+
+    let answers = answers::Answers::parse(&common::read_input("answers.toml")?)?;
+    if !runner::print_verification(&runner::run_all(options.jobs.unwrap_or_else(runner::default_jobs)), &answers) {
+        return Ok(ExitCode::FAILURE)
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn fuzz(args: &[String]) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let (days, rest) = match positional(args) {
+        (Some(d), rest) => (std::slice::from_ref(parse_day(Some(d), "fuzz")?), rest),
+        (None, rest) => (&DAYS[..], rest)
+    };
+
+    let options = parse_options("fuzz", rest, &["--seed", "--iterations"])?;
+    let crashes = fuzz::fuzz(days, options.seed.unwrap_or(0), options.iterations.unwrap_or(10000), true);
+    fuzz::print_crashes(&crashes);
+    if !crashes.is_empty() {
+        return Ok(ExitCode::FAILURE)
+    }
+    println!("No panics in {} day(s)", days.len());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn generate(args: &[String]) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let day = parse_day(args.first(), "generate")?;
+    let options = parse_options("generate", &args[1..], &["--seed", "--size"])?;
+    let seed = options.seed.unwrap_or_else(|| {
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+        now.as_nanos() as u64
+    });
+
+    info!("seed {}", seed);
+    print!("{}", generate::generate(day.day, seed, options.size.unwrap_or(1000)).unwrap());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn bench(args: &[String]) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let day = parse_day(args.first(), "bench")?;
+
+    let options = parse_options("bench", &args[1..], &["--input", "--iterations", "--baseline", "--save-baseline"])?;
+    let iterations = options.iterations.unwrap_or(20);
+    let contents = common::read_input(&options.input.unwrap_or_else(|| day.input_file()))?;
+    let baseline = match options.baseline {
+        None => None,
+        Some(path) => Some(bench::Baseline::parse(&common::read_input(&path)?)?)
+    };
+
+    let benches = bench::bench_day(day, &contents, iterations)?;
+    bench::print_bench(&benches, iterations, baseline.as_ref());
+
+    if let Some(path) = options.save_baseline {
+        std::fs::write(&path, bench::Baseline::render(&benches))?;
+        info!("Saved baseline to {}", path);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn stats(args: &[String]) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let options = parse_options("stats", args, &["--input", "--example"])?;
+    let contents = if options.example {
+        match examples::for_day(1).next() {
+            None => return Err(Box::from("Day 1 has no example")),
+            Some(e) => e.input.to_string()
+        }
+    }
+    else {
+        common::read_input(&options.input.unwrap_or_else(|| DAYS[0].input_file()))?
+    };
+
+    match day1::Stats::new(&day1::parse_input(&common::normalize(&contents))?) {
+        None => Err(Box::from("No elves in the input")),
+        Some(stats) => {
+            for line in stats.render() {
+                println!("{}", line);
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+// Collects --top, the --config file and --set into one list, checked against the day up front so
+// a bad parameter is reported once rather than by every run that uses it.
+fn params(day: &Day, options: &Options) -> Result<std::vec::Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let mut params = vec![];
+    if let Some(k) = options.top {
+        day.check("top", &k.to_string()).map_err(|_| usage_error(&format!("Day {} has no --top option", day.day)))?;
+        params.push(("top".to_string(), k.to_string()));
+    }
+    if let Some(path) = &options.config {
+        for (name, value) in common::parse_params(&common::read_input(path)?).map_err(|e| format!("{} {}", path, e))? {
+            day.check(&name, &value).map_err(|e| format!("{}: {}", path, e))?;
+            params.push((name, value));
+        }
+    }
+    for (name, value) in &options.params {
+        day.check(name, value).map_err(|e| usage_error(&e.to_string()))?;
+        params.push((name.clone(), value.clone()));
+    }
+    Ok(params)
+}
+
+fn run(day: &Day, parts: &[Part], options: Options) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let params = &params(day, &options)?;
+    if options.watch {
+        watch::watch(day, parts, &watch::watched_files(day, parts, options.input, options.example), params);
+    }
+
+    if options.format == Some(Format::Json) {
+        let runs = if options.example {
+            parts.iter()
+                .flat_map(|&part| examples::for_day(day.day)
+                    .filter(move |e| e.expected(part).is_some())
+                    .map(move |e| runner::run_part(day, part, e.name, e.input, params)))
+                .collect()
+        }
+        else {
+            runner::run_file_parts(day, parts, options.input.as_deref().unwrap_or(&day.input_file()), params)
+        };
+
+        runner::print_json(&runs);
+        if runs.iter().any(|r| r.answer.is_err()) {
+            return Ok(ExitCode::FAILURE)
+        }
+        return Ok(ExitCode::SUCCESS)
+    }
+
+    if options.example {
+        let mut ok = true;
+        for &part in parts {
+            if parts.len() > 1 {
+                println!("Part {}:", part.number());
+            }
+            ok &= runner::print_examples(day, part, params);
+        }
+        return Ok(if ok { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+    }
+
+    if options.stream {
+        return stream(day, parts, options)
+    }
+
+    let contents = common::read_input(&options.input.unwrap_or_else(|| day.input_file()))?;
+    let mut puzzle = day.parse(&contents)?;
+    for (name, value) in params {
+        puzzle.set(name, value)?;
+    }
+
+    for &part in parts {
+        print_answer(parts.len(), part, &puzzle.solve(part)?);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_answer(part_count: usize, part: Part, answer: &Answer) {
+    if part_count == 1 {
+        println!("{}", answer);
+    }
+    else {
+        let sep = if let Answer::Grid(_) = answer { "\n" } else { " " };
+        println!("Part {}:{}{}", part.number(), sep, answer);
+    }
+}
+
+fn stream(day: &Day, parts: &[Part], options: Options) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    if day.day != 1 {
+        return Err(usage_error(&format!("Day {} has no --stream option", day.day)))
+    }
+
+    let solution = day1::Solution { top: options.top };
+    let path = options.input.unwrap_or_else(|| day.input_file());
+    let summary = if path == "-" {
+        day1::summarize(std::io::stdin().lock(), solution.needed())?
+    }
+    else {
+        match std::fs::File::open(&path) {
+            Ok(file) => day1::summarize(std::io::BufReader::new(file), solution.needed())?,
+            Err(e) => return Err(Box::from(format!("Failed to read {}: {}", path, e)))
+        }
+    };
+
+    debug!("{} elves carrying {} calories", summary.elves, summary.calories);
+    for &part in parts {
+        print_answer(parts.len(), part, &solution.answer(part, &summary.top)?);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn dispatch(command: &str, args: &[String]) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    match command {
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(ExitCode::SUCCESS)
+        },
+        "list" | "--list" => list(args),
+        "all" | "--all" => all(args),
+        "verify" | "--verify" => verify(args),
+        "bench" | "--bench" => bench(args),
+        "stats" => stats(args),
+        "generate" => generate(args),
+        "fuzz" => fuzz(args),
+        "repl" => {
+            parse_options("repl", args, &[])?;
+            repl::run().map(|_| ExitCode::SUCCESS)
+        },
+        "run" => {
+            let day = parse_day(args.first(), "run")?;
+            let (part, rest) = positional(&args[1..]);
+            let parts = match part {
+                None => PARTS.to_vec(),
+                Some(p) => vec![parse_part(p)?]
+            };
+            run(day, &parts, parse_options("run", rest, RUN_OPTIONS)?)
+        },
+        flag => match legacy_day_flag(flag) {
+            Some((day, part)) => run(day, &[part], parse_options(flag, args, RUN_OPTIONS)?),
+            None => Err(usage_error(&format!("Unknown command {}", flag)))
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args: std::vec::Vec<String> = std::env::args().skip(1).collect();
+    args.retain(|arg| match log::Level::from_flag(arg) {
+        Some(level) => {
+            log::set_level(level);
+            false
+        },
+        None => true
+    });
+
+    let (command, rest) = match args.split_first() {
+        Some(split) => split,
+        None => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2)
+        }
+    };
+
+    match dispatch(command, rest) {
+        Ok(code) => code,
+        Err(e) => {
+            if let Some(parse_error) = e.downcast_ref::<common::ParseError>() {
+                eprintln!("Error: {}", parse_error.render());
+                return ExitCode::FAILURE
+            }
+
+            eprintln!("Error: {}", e);
+            if e.is::<UsageError>() {
+                eprintln!("Run 'aoc help' for usage.");
+                return ExitCode::from(2)
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use crate::common::{read_input, ParseError};
+use crate::examples;
+use crate::runner::millis;
+use crate::solver::{Answer, Day, Part, Puzzle, DAYS};
+use std::io::{BufRead, Write};
+use std::time::Instant;
+
+const HELP: &str = "Commands:
+  load <day> [<path>|example]  parse a day's input (default dayN.txt)
+  part1, part2                 solve a part of the loaded input
+  show                         print the parsed model
+  set <name> <value>           set a solver parameter, e.g. set rounds 500
+  reload                       re-read and re-parse the input, keeping parameters
+  help
+  quit";
+
+#[derive(Default)]
+pub struct Session {
+    day: Option<&'static Day>,
+    source: String,
+    puzzle: Option<Box<dyn Puzzle>>,
+    params: std::vec::Vec<(String, String)>
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session::default()
+    }
+
+    fn puzzle(&self) -> Result<&dyn Puzzle, Box<dyn std::error::Error>> {
+        match &self.puzzle {
+            None => Err(Box::from("Nothing loaded; try load <day>")),
+            Some(p) => Ok(p.as_ref())
+        }
+    }
+
+    fn load(&mut self, day: &'static Day, source: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let contents = if source == "example" {
+            match examples::for_day(day.day).next() {
+                None => return Err(Box::from(format!("Day {} has no example", day.day))),
+                Some(e) => e.input.to_string()
+            }
+        }
+        else {
+            read_input(source)?
+        };
+
+        let start = Instant::now();
+        let mut puzzle = day.parse(&contents)?;
+        let parse_time = start.elapsed();
+
+        // Parameters carry over a reload but not a switch to another day.
+        let params = if self.day.is_some_and(|d| d.day == day.day) { self.params.clone() } else { vec![] };
+        for (name, value) in &params {
+            puzzle.set(name, value)?;
+        }
+
+        self.day = Some(day);
+        self.params = params;
+        self.source = source.to_string();
+        self.puzzle = Some(puzzle);
+        Ok(format!("Loaded day {} from {} in {:.3} ms", day.day, source, millis(parse_time)))
+    }
+
+    fn solve(&self, part: Part) -> Result<String, Box<dyn std::error::Error>> {
+        let start = Instant::now();
+        let answer = self.puzzle()?.solve(part)?;
+        let elapsed = millis(start.elapsed());
+
+        let sep = if let Answer::Grid(_) = answer { "\n" } else { " " };
+        Ok(format!("Part {}:{}{}\n({:.3} ms)", part.number(), sep, answer, elapsed))
+    }
+
+    pub fn execute(&mut self, line: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let words: std::vec::Vec<&str> = line.split_whitespace().collect();
+
+        match words[..] {
+            [] => Ok(String::new()),
+            ["help"] => Ok(HELP.to_string()),
+            ["load", day] | ["load", day, _] => {
+                let day = match day.parse::<usize>() {
+                    Ok(d) if (1..=DAYS.len()).contains(&d) => &DAYS[d - 1],
+                    _ => return Err(Box::from(format!("Expected a day from 1 to {}", DAYS.len())))
+                };
+
+                let source = words.get(2).map_or_else(|| day.input_file(), |s| s.to_string());
+                self.load(day, &source)
+            },
+            ["reload"] => match self.day {
+                None => Err(Box::from("Nothing loaded; try load <day>")),
+                Some(day) => {
+                    let source = self.source.clone();
+                    self.load(day, &source)
+                }
+            },
+            ["part1"] => self.solve(Part::One),
+            ["part2"] => self.solve(Part::Two),
+            ["show"] => Ok(self.puzzle()?.show()),
+            ["set", name, value] => {
+                match &mut self.puzzle {
+                    None => return Err(Box::from("Nothing loaded; try load <day>")),
+                    Some(p) => p.set(name, value)?
+                };
+
+                self.params.retain(|(n, _)| n != name);
+                self.params.push((name.to_string(), value.to_string()));
+                Ok(format!("{} = {}", name, value))
+            },
+            _ => Err(Box::from(format!("Unknown command '{}'; try help", line.trim())))
+        }
+    }
+}
+
+pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let mut session = Session::new();
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        print!("aoc> ");
+        std::io::stdout().flush()?;
+
+        let line = match lines.next() {
+            None => break,
+            Some(line) => line?
+        };
+        if matches!(line.trim(), "quit" | "exit") {
+            break;
+        }
+
+        match session.execute(&line) {
+            Ok(out) if out.is_empty() => {},
+            Ok(out) => println!("{}", out),
+            Err(e) => match e.downcast_ref::<ParseError>() {
+                Some(parse_error) => eprintln!("Error: {}", parse_error.render()),
+                None => eprintln!("Error: {}", e)
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_session() {
+        let mut session = Session::new();
+        assert!(session.execute("part1").is_err());
+        assert!(session.execute("load 13").is_err());
+
+        assert!(session.execute("load 11 example").unwrap().starts_with("Loaded day 11 from example"));
+        assert!(session.execute("part1").unwrap().starts_with("Part 1: 10605\n"));
+        assert!(session.execute("show").unwrap().contains("throw_count"));
+
+        session.execute("set rounds 1").unwrap();
+        assert!(session.execute("set rounds many").is_err());
+        assert!(session.execute("set speed 1").is_err());
+        assert!(session.execute("part1").unwrap().starts_with("Part 1: 20\n"));
+
+        session.execute("reload").unwrap();
+        assert!(session.execute("part1").unwrap().starts_with("Part 1: 20\n"));
+
+        assert!(session.execute("load 2 missing.txt").is_err());
+        session.execute("reload").unwrap();
+        assert!(session.execute("part1").unwrap().starts_with("Part 1: 20\n"));
+
+        session.execute("load 12 example").unwrap();
+        assert!(session.execute("show").unwrap().starts_with("Sabqponm\nabcryxxl\n"));
+
+        session.execute("load 9 day9.ex").unwrap();
+        assert!(session.execute("part1").unwrap().starts_with("Part 1: 13\n"));
+        assert!(session.execute("frobnicate").is_err());
+    }
+}
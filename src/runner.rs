@@ -0,0 +1,369 @@
+use crate::answers::{matches, Answers};
+use crate::common::{read_input, ParseError};
+use crate::examples;
+use crate::solver::{Answer, Day, Part, DAYS, PARTS};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunError {
+    Input(String),
+    Parse(String, Option<ParseError>),
+    Solve(String)
+}
+
+impl RunError {
+    pub fn phase(&self) -> &'static str {
+        match self {
+            RunError::Input(_) => "input",
+            RunError::Parse(_, _) => "parse",
+            RunError::Solve(_) => "solve"
+        }
+    }
+}
+
+impl std::fmt::Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunError::Input(e) | RunError::Parse(e, _) | RunError::Solve(e) => write!(f, "{}", e)
+        }
+    }
+}
+
+pub struct Run {
+    pub day: u32,
+    pub part: Part,
+    pub input: String,
+    pub answer: Result<Answer, RunError>,
+    pub parse_time: Duration,
+    pub solve_time: Duration
+}
+
+pub fn run_part(day: &Day, part: Part, input: &str, contents: &str, params: &[(String, String)]) -> Run {
+    let mut run = Run {
+        day: day.day,
+        part,
+        input: input.to_string(),
+        answer: Err(RunError::Solve(String::new())),
+        parse_time: Duration::ZERO,
+        solve_time: Duration::ZERO
+    };
+
+    let start = Instant::now();
+    let puzzle = day.parse(contents);
+    run.parse_time = start.elapsed();
+
+    let mut puzzle = match puzzle {
+        Ok(p) => p,
+        Err(e) => {
+            run.answer = Err(RunError::Parse(e.to_string(), e.downcast_ref::<ParseError>().cloned()));
+            return run
+        }
+    };
+
+    for (name, value) in params {
+        if let Err(e) = puzzle.set(name, value) {
+            run.answer = Err(RunError::Solve(e.to_string()));
+            return run
+        }
+    }
+
+    let start = Instant::now();
+    let answer = puzzle.solve(part);
+    run.solve_time = start.elapsed();
+
+    run.answer = answer.map_err(|e| RunError::Solve(e.to_string()));
+    crate::debug!("Day {} part {}: parsed in {:.3} ms, solved in {:.3} ms",
+        run.day, part.number(), millis(run.parse_time), millis(run.solve_time));
+    run
+}
+
+fn run_input(day: &Day, part: Part, input: &str, contents: &Result<String, String>, params: &[(String, String)]) -> Run {
+    match contents {
+        Ok(c) => run_part(day, part, input, c, params),
+        Err(e) => Run {
+            day: day.day,
+            part,
+            input: input.to_string(),
+            answer: Err(RunError::Input(e.clone())),
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO
+        }
+    }
+}
+
+pub fn run_file(day: &Day, part: Part, path: &str, params: &[(String, String)]) -> Run {
+    run_input(day, part, path, &read_input(path).map_err(|e| e.to_string()), params)
+}
+
+// Reads the input once for all the parts, so stdin ("-") isn't drained by the first part.
+pub fn run_file_parts(day: &Day, parts: &[Part], path: &str, params: &[(String, String)]) -> std::vec::Vec<Run> {
+    run_parts(day, parts, path, &read_input(path).map_err(|e| e.to_string()), params)
+}
+
+pub fn run_parts(day: &Day, parts: &[Part], input: &str, contents: &Result<String, String>, params: &[(String, String)]) -> std::vec::Vec<Run> {
+    parts.iter().map(|&part| run_input(day, part, input, contents, params)).collect()
+}
+
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+// Workers pull (day, part) jobs off a shared counter; runs come back in DAYS order whatever finishes first.
+pub fn run_all(jobs: usize) -> std::vec::Vec<Run> {
+    let inputs: std::vec::Vec<Result<String, String>> = DAYS.iter()
+        .map(|day| read_input(&day.input_file()).map_err(|e| e.to_string()))
+        .collect();
+    let tasks: std::vec::Vec<(usize, Part)> = (0..DAYS.len())
+        .flat_map(|d| PARTS.map(|part| (d, part)))
+        .collect();
+    let next = AtomicUsize::new(0);
+
+    let mut runs: std::vec::Vec<(usize, Run)> = thread::scope(|scope| {
+        let workers: std::vec::Vec<_> = (0..jobs.clamp(1, tasks.len()))
+            .map(|_| scope.spawn(|| {
+                let mut done = vec![];
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    match tasks.get(i) {
+                        None => break,
+                        Some(&(d, part)) => done.push((i, run_input(&DAYS[d], part, &DAYS[d].input_file(), &inputs[d], &[])))
+                    }
+                }
+                done
+            }))
+            .collect();
+
+        workers.into_iter().flat_map(|w| w.join().unwrap()).collect()
+    });
+
+    runs.sort_by_key(|(i, _)| *i);
+    runs.into_iter().map(|(_, run)| run).collect()
+}
+
+pub fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+fn summarize(answer: &Answer) -> String {
+    match answer {
+        Answer::Grid(rows) => format!("<{} row grid>", rows.len()),
+        a => a.to_string()
+    }
+}
+
+fn summarize_result(answer: &Result<Answer, RunError>) -> String {
+    match answer {
+        Ok(a) => summarize(a),
+        Err(e) => format!("error: {}", e)
+    }
+}
+
+pub fn print_table(runs: &[Run]) {
+    println!("{:>4} {:>5}  {:<24} {:>12} {:>12}", "Day", "Part", "Answer", "Parse (ms)", "Solve (ms)");
+
+    let mut total_parse = Duration::ZERO;
+    let mut total_solve = Duration::ZERO;
+    for run in runs {
+        println!("{:>4} {:>5}  {:<24} {:>12.3} {:>12.3}",
+            run.day,
+            run.part.number(),
+            summarize_result(&run.answer),
+            millis(run.parse_time),
+            millis(run.solve_time));
+
+        total_parse += run.parse_time;
+        total_solve += run.solve_time;
+    }
+
+    println!("{:>4} {:>5}  {:<24} {:>12.3} {:>12.3}", "", "", "Total", millis(total_parse), millis(total_solve));
+}
+
+pub fn print_verification(runs: &[Run], answers: &Answers) -> bool {
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut missing = 0;
+
+    for run in runs {
+        let status = match (answers.get(run.day, run.part), &run.answer) {
+            (_, Err(e)) => {
+                failed += 1;
+                format!("FAIL (error: {})", e)
+            },
+            (None, Ok(_)) => {
+                missing += 1;
+                "missing".to_string()
+            },
+            (Some(expected), Ok(actual)) => {
+                if matches(expected, actual) {
+                    passed += 1;
+                    "pass".to_string()
+                }
+                else {
+                    failed += 1;
+                    format!("FAIL (expected {}, got {})", summarize(expected), summarize(actual))
+                }
+            }
+        };
+
+        println!("Day {} part {}: {}", run.day, run.part.number(), status);
+    }
+
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    failed == 0
+}
+
+pub fn print_examples(day: &Day, part: Part, params: &[(String, String)]) -> bool {
+    let mut ok = true;
+
+    for example in examples::for_day(day.day).filter(|e| e.expected(part).is_some()) {
+        let run = run_part(day, part, example.name, example.input, params);
+        match &run.answer {
+            Err(e) => {
+                ok = false;
+                println!("{}: error: {}", example.name, e);
+            },
+            Ok(answer) => {
+                let sep = if let Answer::Grid(_) = answer { "\n" } else { " " };
+                println!("{}:{}{}", example.name, sep, answer);
+                if example.check(part, answer) == Some(false) {
+                    ok = false;
+                    println!("{}: expected {}", example.name, summarize(&example.expected(part).unwrap()));
+                }
+            }
+        }
+    }
+
+    ok
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            '\n' => out += "\\n",
+            '\r' => out += "\\r",
+            '\t' => out += "\\t",
+            c if (c as u32) < 0x20 => out += &format!("\\u{:04x}", c as u32),
+            c => out.push(c)
+        }
+    }
+    out + "\""
+}
+
+fn answer_json(answer: &Answer) -> String {
+    match answer {
+        Answer::Int(v) => v.to_string(),
+        Answer::Text(s) => json_string(s),
+        Answer::Grid(rows) => format!("[{}]", rows.iter().map(|r| json_string(r)).collect::<std::vec::Vec<_>>().join(","))
+    }
+}
+
+fn error_json(error: &RunError) -> String {
+    let mut fields = vec![
+        format!("\"phase\":{}", json_string(error.phase())),
+        format!("\"message\":{}", json_string(&error.to_string()))
+    ];
+
+    if let RunError::Parse(_, Some(e)) = error {
+        fields.push(format!("\"line\":{}", e.line));
+        fields.push(format!("\"column\":{}", e.column));
+        fields.push(format!("\"text\":{}", json_string(&e.text)));
+        fields.push(format!("\"expected\":{}", json_string(&e.expected)));
+    }
+
+    format!("{{{}}}", fields.join(","))
+}
+
+// One object per line, so a stream of runs can be consumed as JSON Lines.
+pub fn to_json(run: &Run) -> String {
+    let (answer, error) = match &run.answer {
+        Ok(a) => (answer_json(a), "null".to_string()),
+        Err(e) => ("null".to_string(), error_json(e))
+    };
+
+    format!("{{\"day\":{},\"part\":{},\"input\":{},\"answer\":{},\"parse_ms\":{:.6},\"solve_ms\":{:.6},\"error\":{}}}",
+        run.day,
+        run.part.number(),
+        json_string(&run.input),
+        answer,
+        millis(run.parse_time),
+        millis(run.solve_time),
+        error)
+}
+
+pub fn print_json(runs: &[Run]) {
+    for run in runs {
+        println!("{}", to_json(run));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_run_all_keeps_order() {
+        let serial = run_all(1);
+        let parallel = run_all(4);
+
+        assert_eq!(serial.len(), DAYS.len() * PARTS.len());
+        for (a, b) in serial.iter().zip(&parallel) {
+            assert_eq!((a.day, a.part), (b.day, b.part));
+            assert_eq!(a.answer, b.answer);
+        }
+    }
+
+    #[test]
+    fn test_run_parts() {
+        let runs = run_parts(&DAYS[3], &PARTS, "-", &Ok("2-4,6-8\n2-8,3-7\n5-7,7-9\n".to_string()), &[]);
+        let answers: std::vec::Vec<_> = runs.iter().map(|r| r.answer.clone()).collect();
+        assert_eq!(answers, vec![Ok(Answer::Int(1)), Ok(Answer::Int(2))]);
+
+        let runs = run_parts(&DAYS[3], &PARTS, "missing", &Err("gone".to_string()), &[]);
+        assert!(runs.iter().all(|r| r.answer == Err(RunError::Input("gone".to_string()))));
+
+        let top = [("top".to_string(), "1".to_string())];
+        assert_eq!(run_part(&DAYS[0], Part::Two, "-", "1\n2\n\n3\n", &[]).answer, Ok(Answer::Int(6)));
+        assert_eq!(run_part(&DAYS[0], Part::Two, "-", "1\n2\n\n3\n", &top).answer, Ok(Answer::Int(3)));
+
+        let unknown = [("rounds".to_string(), "1".to_string())];
+        assert_eq!(run_part(&DAYS[0], Part::Two, "-", "1\n", &unknown).answer, Err(RunError::Solve("Unknown parameter rounds".to_string())));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let day = &DAYS[0];
+        let run = run_part(day, Part::One, "a \"quoted\" path", "1\n2\n\n3\n", &[]);
+        let json = to_json(&run);
+        assert!(json.starts_with("{\"day\":1,\"part\":1,\"input\":\"a \\\"quoted\\\" path\",\"answer\":3,"), "{}", json);
+        assert!(json.ends_with(",\"error\":null}"), "{}", json);
+
+        let run = run_part(day, Part::One, "bad", "1\nx\n", &[]);
+        let json = to_json(&run);
+        assert!(json.contains("\"answer\":null"), "{}", json);
+        assert!(json.contains("\"error\":{\"phase\":\"parse\","), "{}", json);
+        assert!(json.contains("\"line\":2,\"column\":1,\"text\":\"x\",\"expected\":\"a calorie count for elf 1\"}"), "{}", json);
+
+        assert_eq!(answer_json(&Answer::Grid(vec!["#.".to_string(), ".#".to_string()])), "[\"#.\",\".#\"]");
+    }
+}
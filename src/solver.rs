@@ -0,0 +1,187 @@
+use crate::common::normalize;
+use crate::{day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two
+}
+
+impl Part {
+    pub fn number(&self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2
+        }
+    }
+}
+
+pub const PARTS: [Part; 2] = [Part::One, Part::Two];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    Grid(std::vec::Vec<String>)
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(v) => write!(f, "{}", v),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n"))
+        }
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(v: i32) -> Answer {
+        Answer::Int(v as i64)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(v: u32) -> Answer {
+        Answer::Int(v as i64)
+    }
+}
+
+// u64 and usize answers can be too large for Answer::Int, so converting them can fail.
+impl TryFrom<u64> for Answer {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(v: u64) -> Result<Answer, Self::Error> {
+        match i64::try_from(v) {
+            Ok(v) => Ok(Answer::Int(v)),
+            Err(_) => Err(Box::from(format!("Answer {} is too large", v)))
+        }
+    }
+}
+
+impl TryFrom<usize> for Answer {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(v: usize) -> Result<Answer, Self::Error> {
+        Answer::try_from(v as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+pub trait Solver {
+    type Parsed: std::fmt::Debug;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Box<dyn std::error::Error>>;
+    fn part1(&self, parsed: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>>;
+    fn part2(&self, parsed: &Self::Parsed) -> Result<Answer, Box<dyn std::error::Error>>;
+
+    fn set(&mut self, name: &str, _value: &str) -> Result<(), Box<dyn std::error::Error>> {
+        Err(Box::from(format!("Unknown parameter {}", name)))
+    }
+
+    fn show(&self, parsed: &Self::Parsed) -> String {
+        format!("{:#?}", parsed)
+    }
+}
+
+pub trait Puzzle {
+    fn solve(&self, part: Part) -> Result<Answer, Box<dyn std::error::Error>>;
+    fn show(&self) -> String;
+    fn set(&mut self, name: &str, value: &str) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+struct Parsed<S: Solver> {
+    solver: S,
+    parsed: S::Parsed
+}
+
+impl<S: Solver> Puzzle for Parsed<S> {
+    fn solve(&self, part: Part) -> Result<Answer, Box<dyn std::error::Error>> {
+        match part {
+            Part::One => self.solver.part1(&self.parsed),
+            Part::Two => self.solver.part2(&self.parsed)
+        }
+    }
+
+    fn show(&self) -> String {
+        self.solver.show(&self.parsed)
+    }
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.solver.set(name, value)
+    }
+}
+
+type ParseFn = fn(&str) -> Result<Box<dyn Puzzle>, Box<dyn std::error::Error>>;
+
+fn parse_with<S: Solver + Default + 'static>(input: &str) -> Result<Box<dyn Puzzle>, Box<dyn std::error::Error>> {
+    let solver = S::default();
+    let parsed = solver.parse(input)?;
+    Ok(Box::new(Parsed { solver, parsed }))
+}
+
+type CheckFn = fn(&str, &str) -> Result<(), Box<dyn std::error::Error>>;
+
+fn check_with<S: Solver + Default>(name: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+    S::default().set(name, value)
+}
+
+pub struct Day {
+    pub day: u32,
+    parse: ParseFn,
+    check: CheckFn
+}
+
+impl Day {
+    pub fn input_file(&self) -> String {
+        format!("day{}.txt", self.day)
+    }
+
+    pub fn flag(&self, part: Part) -> String {
+        format!("--day{}-part{}", self.day, part.number())
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Puzzle>, Box<dyn std::error::Error>> {
+        (self.parse)(&normalize(input))
+    }
+
+    // Validates a parameter without any input, so it can be rejected before anything runs.
+    pub fn check(&self, name: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+        (self.check)(name, value)
+    }
+}
+
+pub static DAYS: [Day; 12] = [
+    Day { day: 1, parse: parse_with::<day1::Solution>, check: check_with::<day1::Solution> },
+    Day { day: 2, parse: parse_with::<day2::Solution>, check: check_with::<day2::Solution> },
+    Day { day: 3, parse: parse_with::<day3::Solution>, check: check_with::<day3::Solution> },
+    Day { day: 4, parse: parse_with::<day4::Solution>, check: check_with::<day4::Solution> },
+    Day { day: 5, parse: parse_with::<day5::Solution>, check: check_with::<day5::Solution> },
+    Day { day: 6, parse: parse_with::<day6::Solution>, check: check_with::<day6::Solution> },
+    Day { day: 7, parse: parse_with::<day7::Solution>, check: check_with::<day7::Solution> },
+    Day { day: 8, parse: parse_with::<day8::Solution>, check: check_with::<day8::Solution> },
+    Day { day: 9, parse: parse_with::<day9::Solution>, check: check_with::<day9::Solution> },
+    Day { day: 10, parse: parse_with::<day10::Solution>, check: check_with::<day10::Solution> },
+    Day { day: 11, parse: parse_with::<day11::Solution>, check: check_with::<day11::Solution> },
+    Day { day: 12, parse: parse_with::<day12::Solution>, check: check_with::<day12::Solution> },
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(42).to_string(), "42");
+        assert_eq!(Answer::try_from(19573408701u64).unwrap().to_string(), "19573408701");
+        assert!(Answer::try_from(u64::MAX).is_err());
+        assert!(Answer::try_from(usize::MAX).is_err());
+        assert_eq!(Answer::from("MRJ".to_string()).to_string(), "MRJ");
+        assert_eq!(Answer::Grid(vec!["#.".to_string(), ".#".to_string()]).to_string(), "#.\n.#");
+    }
+}
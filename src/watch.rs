@@ -0,0 +1,85 @@
+use crate::examples::{self, Example};
+use crate::runner::{millis, run_file, Run};
+use crate::solver::{Answer, Day, Part};
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+pub struct Watched {
+    pub path: String,
+    pub example: Option<&'static Example>
+}
+
+// The day's input (unless only examples were asked for) followed by its example files,
+// read from disk on every run so hand edits show up without a rebuild.
+pub fn watched_files(day: &Day, parts: &[Part], input: Option<String>, examples_only: bool) -> std::vec::Vec<Watched> {
+    let mut files = vec![];
+    if !examples_only {
+        files.push(Watched { path: input.unwrap_or_else(|| day.input_file()), example: None });
+    }
+
+    for example in examples::for_day(day.day).filter(|e| parts.iter().any(|&p| e.expected(p).is_some())) {
+        files.push(Watched { path: example.name.to_string(), example: Some(example) });
+    }
+
+    files
+}
+
+pub fn snapshot(files: &[Watched]) -> std::vec::Vec<Option<SystemTime>> {
+    files.iter()
+        .map(|f| std::fs::metadata(&f.path).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+fn print_run(file: &Watched, run: &Run) {
+    match &run.answer {
+        Err(e) => println!("{}: error: {}", file.path, e),
+        Ok(answer) => {
+            let sep = if let Answer::Grid(_) = answer { "\n" } else { " " };
+            println!("{} part {}:{}{}", file.path, run.part.number(), sep, answer);
+            if let Some(expected) = file.example.filter(|e| e.check(run.part, answer) == Some(false)).and_then(|e| e.expected(run.part)) {
+                println!("{}: expected {}", file.path, expected);
+            }
+        }
+    }
+    println!("  parse {:.3} ms, solve {:.3} ms", millis(run.parse_time), millis(run.solve_time));
+}
+
+pub fn watch(day: &Day, parts: &[Part], files: &[Watched], params: &[(String, String)]) -> ! {
+    loop {
+        let seen = snapshot(files);
+
+        print!("\x1b[2J\x1b[H");
+        println!("Day {}, watching {} file(s)", day.day, files.len());
+        for file in files {
+            for &part in parts.iter().filter(|&&p| file.example.is_none_or(|e| e.expected(p).is_some())) {
+                print_run(file, &run_file(day, part, &file.path, params));
+            }
+        }
+
+        while snapshot(files) == seen {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solver::DAYS;
+
+    #[test]
+    fn test_watched_files() {
+        let files = watched_files(&DAYS[8], &[Part::Two], None, false);
+        let paths: std::vec::Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["day9.txt", "day9.ex", "day9.ex2"]);
+
+        let files = watched_files(&DAYS[8], &[Part::One], Some("other.txt".to_string()), true);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "day9.ex");
+
+        let times = snapshot(&[Watched { path: "missing.txt".to_string(), example: None }]);
+        assert_eq!(times, vec![None]);
+        assert!(snapshot(&files)[0].is_some());
+    }
+}